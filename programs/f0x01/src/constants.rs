@@ -2,3 +2,192 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+#[constant]
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+// reward rates used to be whole percents; a legacy value maps to `percent * BPS_PER_PERCENT`
+#[constant]
+pub const BPS_PER_PERCENT: u64 = 100;
+
+// completion needed for the stake plus bonus
+#[constant]
+pub const BONUS_THRESHOLD_BPS: u64 = 9_000;
+
+// cancelling on the first day costs the same 25% as falling below the failure threshold
+#[constant]
+pub const DEFAULT_MAX_EARLY_PENALTY_BPS: u64 = 2_500;
+
+// completion below this only refunds part of the stake; commitments may raise it up to
+// BONUS_THRESHOLD_BPS but not lower it
+#[constant]
+pub const DEFAULT_FAIL_THRESHOLD_BPS: u64 = 7_500;
+
+#[constant]
+pub const MAX_PAUSES_PER_COMMITMENT: u8 = 3;
+
+// how long after its deadline an unclaimed commitment can still be revived
+#[constant]
+pub const REVIVAL_WINDOW_SECS: i64 = 7 * 86400;
+
+#[constant]
+pub const MAX_REVIVALS_PER_COMMITMENT: u8 = 1;
+
+#[constant]
+pub const SECONDS_PER_DAY: i64 = 86400;
+
+#[constant]
+pub const SECONDS_PER_WEEK: i64 = 7 * 86400;
+
+// shortest stats period rollover_period_stats will archive
+#[constant]
+pub const MIN_STATS_PERIOD_SECS: i64 = 86400;
+
+#[constant]
+pub const SECONDS_PER_HOUR: i64 = 3600;
+
+// 1970-01-01 was a Thursday, weekday 3 counting Monday as 0
+#[constant]
+pub const EPOCH_WEEKDAY: i64 = 3;
+
+// furthest a commitment's local day may be from UTC, in either direction
+#[constant]
+pub const MAX_DAY_OFFSET_SECS: i32 = 14 * 3600;
+
+// 25 min focus + 5 min break + 25 min focus
+#[constant]
+pub const SESSION_DURATION_SECS: i64 = 55 * 60;
+
+#[constant]
+pub const MIN_SESSION_GAP_SECS: i64 = 30 * 60;
+
+// returned by next_session_eligible_at when the commitment allows no further sessions
+#[constant]
+pub const NO_ELIGIBLE_SESSION: i64 = -1;
+
+#[constant]
+pub const MAX_SESSIONS_PER_DAY: u8 = 10;
+
+#[constant]
+pub const MAX_COMMITMENT_DAYS: u8 = 30;
+
+// progress snapshots checkpoint_commitment keeps per term
+pub const MAX_CHECKPOINTS: usize = 8;
+
+// most commitments create_commitment_batch initializes in one transaction, bounded by compute
+pub const MAX_COMMITMENT_BATCH: usize = 4;
+
+// largest timezone difference between two matched accountability partners
+#[constant]
+pub const MAX_PARTNER_TIMEZONE_GAP_MINS: i16 = 120;
+
+// approximate mainnet slot time used to convert slot deltas into seconds, until set_ms_per_slot
+// changes it for a cluster with slower or faster slots
+#[constant]
+pub const DEFAULT_MS_PER_SLOT: u64 = 400;
+
+#[constant]
+pub const MIN_MS_PER_SLOT: u64 = 100;
+
+#[constant]
+pub const MAX_MS_PER_SLOT: u64 = 2_000;
+
+// enough to absorb block timing, too little to matter for a 55 minute session
+#[constant]
+pub const MAX_DURATION_TOLERANCE_SECS: i64 = 60;
+
+// how far before now a client-supplied session start may be, until set_max_backdate changes it
+#[constant]
+pub const DEFAULT_MAX_BACKDATE_SECS: i64 = 86400;
+
+#[constant]
+pub const MAX_BACKDATE_SECS: i64 = 7 * 86400;
+
+// platform tags complete_session accepts for per-platform analytics, 0 = untagged
+#[constant]
+pub const PLATFORM_IOS: u8 = 1;
+
+#[constant]
+pub const PLATFORM_ANDROID: u8 = 2;
+
+#[constant]
+pub const PLATFORM_WEB: u8 = 3;
+
+#[constant]
+pub const PLATFORM_DESKTOP: u8 = 4;
+
+pub const PLATFORM_COUNT: usize = 4;
+
+// longest claim cooldown, so a rate-limited bonus is never held back for long
+#[constant]
+pub const MAX_CLAIM_COOLDOWN_SECS: i64 = 7 * 86400;
+
+// auto_tune_reward_rate raises the rate below this average completion and lowers it above
+// TUNE_HIGH_COMPLETION_BPS; in between the rate is left alone
+#[constant]
+pub const TUNE_LOW_COMPLETION_BPS: u64 = 6_000;
+
+#[constant]
+pub const TUNE_HIGH_COMPLETION_BPS: u64 = 9_000;
+
+// shortest time between two auto_tune_reward_rate steps
+#[constant]
+pub const RATE_TUNE_INTERVAL_SECS: i64 = 86400;
+
+// largest per-day perfect-streak bonus; a full 31 day streak then adds at most 31% of the stake
+#[constant]
+pub const MAX_PERFECT_STREAK_BONUS_BPS: u64 = 100;
+
+// early-bird bonus for a term whose every session finished before early_bird_hour
+#[constant]
+pub const MAX_EARLY_BIRD_BONUS_BPS: u64 = 500;
+
+#[constant]
+pub const DEFAULT_MAX_CLOCK_DRIFT_BPS: u64 = 5_000;
+
+// domain prefix hashed with the session record and nonce for complete_session's proof of work
+#[constant]
+pub const POW_SEED: &[u8] = b"focus_pow";
+
+// leading zero bits; each extra bit doubles the expected client work
+#[constant]
+pub const MAX_POW_DIFFICULTY: u8 = 24;
+
+// domain prefix hashed into every focus tick leaf
+#[constant]
+pub const FOCUS_TICK_SEED: &[u8] = b"focus_tick";
+
+// deep enough for a tick per second over a 55 minute session
+#[constant]
+pub const MAX_EVENT_PROOF_DEPTH: u8 = 16;
+
+// every this-many-day streak counts as a milestone for auto-staking
+#[constant]
+pub const STREAK_MILESTONE_DAYS: u16 = 7;
+
+// share of not-yet-restaked rewards moved into the stake at each milestone
+#[constant]
+pub const AUTO_STAKE_BPS: u64 = 1_000;
+
+// reputation = sessions * REPUTATION_PER_SESSION + best_streak * REPUTATION_PER_STREAK_DAY
+// + total_rewards_earned / REPUTATION_REWARD_UNIT, saturating at u32::MAX
+#[constant]
+pub const REPUTATION_PER_SESSION: u64 = 10;
+
+#[constant]
+pub const REPUTATION_PER_STREAK_DAY: u64 = 25;
+
+// raw reward units per reputation point, one whole token of a 6 decimal mint
+#[constant]
+pub const REPUTATION_REWARD_UNIT: u64 = 1_000_000;
+
+// first byte of an export_commitment blob, bumped whenever its layout changes
+#[constant]
+pub const COMMITMENT_EXPORT_VERSION: u8 = 1;
+
+// size of the allowlist of programs that may create commitments via CPI
+pub const MAX_AUTHORIZED_CALLERS: usize = 4;
+
+// best_streak, in days, needed for each streak airdrop tier
+#[constant]
+pub const STREAK_AIRDROP_TIER_DAYS: [u16; 3] = [7, 30, 100];
@@ -26,4 +26,222 @@ pub enum FocusError {
     InsufficientBalance,
     #[msg("invalid authority")]
     InvalidAuthority,
-}
\ No newline at end of file
+    #[msg("reward rate must be greater than zero")]
+    InvalidRewardRate,
+    #[msg("commitment is paused")]
+    CommitmentPaused,
+    #[msg("commitment is not paused")]
+    CommitmentNotPaused,
+    #[msg("commitment has used all of its pauses")]
+    PauseLimitReached,
+    #[msg("the same account was passed more than once")]
+    DuplicateAccount,
+    #[msg("session record belongs to a different commitment")]
+    SessionCommitmentMismatch,
+    #[msg("commitment is shorter than the program minimum")]
+    CommitmentTooShort,
+    #[msg("reminder hour must be between 0 and 23")]
+    InvalidReminderHour,
+    #[msg("timezone offset must be within +/-14 hours")]
+    InvalidTimezoneOffset,
+    #[msg("commitment already has an accountability partner")]
+    AlreadyPartnered,
+    #[msg("partner listings are not compatible")]
+    IncompatiblePartners,
+    #[msg("program is paused")]
+    ProgramPaused,
+    #[msg("program is not paused")]
+    ProgramNotPaused,
+    #[msg("first session reward is not enabled")]
+    FirstSessionRewardDisabled,
+    #[msg("no session has been completed yet")]
+    NoSessionCompleted,
+    #[msg("session clock and slot timing disagree")]
+    ClockDriftDetected,
+    #[msg("basis point value out of range")]
+    InvalidBasisPoints,
+    #[msg("boost window must end in the future")]
+    InvalidBoostWindow,
+    #[msg("session id must equal the commitment's next_session_id")]
+    InvalidSessionId,
+    #[msg("treasury has not been set")]
+    TreasuryNotSet,
+    #[msg("amount is larger than one whole token")]
+    DustAmountTooLarge,
+    #[msg("reward pool would not cover outstanding bonuses")]
+    BonusLiabilityUnderfunded,
+    #[msg("focus window hours must be between 0 and 23")]
+    InvalidFocusWindow,
+    #[msg("session is outside the commitment's focus window")]
+    OutsideFocusWindow,
+    #[msg("sponsor cannot sponsor their own commitment")]
+    InvalidSponsor,
+    #[msg("payout account is not owned by the commitment's sponsor")]
+    InvalidPayoutAccount,
+    #[msg("focus event proof does not match the submitted root")]
+    InvalidEventProof,
+    #[msg("session has not proven enough focus ticks")]
+    FocusTicksUnverified,
+    #[msg("duration tolerance is out of range")]
+    InvalidDurationTolerance,
+    #[msg("not available on sponsored commitments")]
+    SponsoredCommitment,
+    #[msg("failure threshold must be between the default and the bonus threshold")]
+    InvalidFailThreshold,
+    #[msg("commitment already has sessions")]
+    CommitmentAlreadyStarted,
+    #[msg("calling program is not authorized")]
+    UnauthorizedCaller,
+    #[msg("program is already an authorized caller")]
+    CallerAlreadyAuthorized,
+    #[msg("authorized caller list is full")]
+    CallerListFull,
+    #[msg("program is not an authorized caller")]
+    CallerNotFound,
+    #[msg("invalid referrer")]
+    InvalidReferrer,
+    #[msg("referrer can no longer be set")]
+    ReferrerAlreadySet,
+    #[msg("stake would exceed the program's total staked cap")]
+    TvlCapExceeded,
+    #[msg("invalid streak airdrop tier")]
+    InvalidAirdropTier,
+    #[msg("best streak has not reached this tier")]
+    StreakTierNotReached,
+    #[msg("streak airdrop tier already claimed")]
+    AirdropAlreadyClaimed,
+    #[msg("catch-up gap must not exceed the normal session gap")]
+    InvalidCatchUpGap,
+    #[msg("commitment has not been claimed")]
+    CommitmentNotClaimed,
+    #[msg("commitment vault holds less than the payout")]
+    InsufficientVaultBalance,
+    #[msg("revival window has closed")]
+    RevivalWindowClosed,
+    #[msg("commitment has already been revived")]
+    RevivalLimitReached,
+    #[msg("challenge window must end after it starts")]
+    InvalidChallengeWindow,
+    #[msg("league week has not ended yet")]
+    LeagueWeekNotEnded,
+    #[msg("session predates its commitment")]
+    ImplausibleTimeline,
+    #[msg("migration ratio must be greater than zero")]
+    InvalidMigrationRatio,
+    #[msg("new mint must differ from the current mint")]
+    InvalidMigrationMint,
+    #[msg("mint migration has not started")]
+    MintMigrationNotStarted,
+    #[msg("vault already holds the new mint")]
+    VaultAlreadyMigrated,
+    #[msg("commitment is locked")]
+    CommitmentLocked,
+    #[msg("proof-of-work difficulty is out of range")]
+    InvalidPowDifficulty,
+    #[msg("proof-of-work nonce does not meet the difficulty")]
+    InvalidProofOfWork,
+    #[msg("program is deprecated")]
+    ProgramDeprecated,
+    #[msg("program is not deprecated")]
+    ProgramNotDeprecated,
+    #[msg("commitment does not auto-renew")]
+    AutoRenewDisabled,
+    #[msg("stake is below the program minimum")]
+    StakeBelowMinimum,
+    #[msg("signer is not the program's habit oracle")]
+    InvalidOracle,
+    #[msg("attested completed days exceed attested days")]
+    InvalidAttestation,
+    #[msg("the program's mint_authority PDA is not the token mint's authority")]
+    MintAuthorityNotHeld,
+    #[msg("bonus exceeds what may still be minted this epoch")]
+    EpochMintCapExceeded,
+    #[msg("expected (commitment, vault) account pairs")]
+    InvalidCommitmentAccounts,
+    #[msg("all of the commitment's expected sessions are already completed")]
+    CommitmentSessionsExhausted,
+    #[msg("charity account is not set")]
+    CharityNotSet,
+    #[msg("partial credit floor must be shorter than a full session")]
+    InvalidMinCreditSecs,
+    #[msg("claim_all_rewards only supports the default funding sources")]
+    CustomFundingSources,
+    #[msg("reminder offset must be shorter than a day")]
+    InvalidReminderOffset,
+    #[msg("session overlaps a session that was already completed")]
+    OverlappingSession,
+    #[msg("commitment can no longer reach the auto-fail threshold")]
+    CommitmentAutoFailed,
+    #[msg("strict claim verification needs the session records, which only claim_rewards takes")]
+    StrictClaimVerification,
+    #[msg("stats period is shorter than the minimum")]
+    StatsPeriodNotEnded,
+    #[msg("session slots need one non-empty window per daily session")]
+    InvalidSessionSlots,
+    #[msg("session is outside the window of the next session slot")]
+    OutsideSessionSlot,
+    #[msg("commitment's vault is closed or no longer holds the stake")]
+    VaultMissing,
+    #[msg("timestamp is further in the past than the program allows")]
+    BackdateTooFar,
+    #[msg("max backdate must be between 0 and 7 days")]
+    InvalidMaxBackdate,
+    #[msg("claim cooldown has not passed since the last bonus claim")]
+    ClaimCooldownActive,
+    #[msg("claim cooldown must be between 0 and 7 days")]
+    InvalidClaimCooldown,
+    #[msg("reward curve needs 75% <= floor <= 90%, floor < ceiling <= 100% and a bonus of at most 100%")]
+    InvalidRewardCurve,
+    #[msg("platform tag must be one of the PLATFORM_* constants")]
+    InvalidPlatform,
+    #[msg("global_stats must be passed to record a session's platform")]
+    GlobalStatsRequired,
+    #[msg("daily minutes goal can't be longer than a day")]
+    InvalidMinutesGoal,
+    #[msg("a session with this id was already started")]
+    SessionAlreadyStarted,
+    #[msg("rate tuning needs 0 < min rate <= max rate <= 100%")]
+    InvalidRateTuning,
+    #[msg("reward rate tuning is switched off")]
+    RateTuningDisabled,
+    #[msg("the reward rate was tuned too recently")]
+    RateTuneNotDue,
+    #[msg("no terms have been claimed yet")]
+    NoCompletionData,
+    #[msg("perfect streak bonus is above the maximum")]
+    InvalidPerfectStreakBonus,
+    #[msg("stake is too low for the commitment's number of days")]
+    StakeTooLowForDuration,
+    #[msg("checkpoint interval must be between 0 and a full 30 day term")]
+    InvalidCheckpointInterval,
+    #[msg("commitment checkpoints are switched off")]
+    CheckpointsDisabled,
+    #[msg("the next checkpoint interval hasn't passed yet")]
+    CheckpointNotDue,
+    #[msg("the commitment's checkpoint history is full")]
+    CheckpointHistoryFull,
+    #[msg("slot time must be between 100ms and 2000ms")]
+    InvalidMsPerSlot,
+    #[msg("gift amount must be greater than zero")]
+    InvalidGiftAmount,
+    #[msg("early-bird hour must be below 24 and its bonus at most 5%")]
+    InvalidEarlyBird,
+    #[msg("a commitment batch must hold between one and four phases")]
+    InvalidCommitmentBatch,
+    #[msg("total amount does not match the sum of the phase stakes")]
+    BatchTotalMismatch,
+    #[msg("commitment has not started yet")]
+    CommitmentNotStarted,
+    #[msg("bonuses must be paid from the reward pool")]
+    InvalidBonusSource,
+    #[msg("applying the audit needs every active commitment")]
+    IncompleteAudit,
+    #[msg("program account is not in the first-deployed layout")]
+    ProgramNotUpgradable,
+    #[msg("this claim pays through the reward pool, which must be passed")]
+    RewardPoolRequired,
+    #[msg("rent payer does not match the one that paid for the session record")]
+    InvalidRentPayer,
+    #[msg("the previous league week must be archived with reset_weekly_stats first")]
+    LeagueWeekNotArchived,
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+// claim_rewards landed below the commitment's failure threshold
+#[event]
+pub struct CommitmentFailed {
+    pub commitment: Pubkey,
+    pub user: Pubkey,
+    pub success_bps: u16,
+    pub sessions_missed: u16,
+    pub days_short: u8,
+}
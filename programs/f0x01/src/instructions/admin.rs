@@ -0,0 +1,860 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{Mint, TokenAccount};
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+use crate::utils::check_backdated_timestamp;
+//authority-only program configuration
+
+#[derive(Accounts)]
+pub struct UpdateProgramConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn set_reward_rate(ctx: Context<UpdateProgramConfig>, reward_rate_bps: u64) -> Result<()> {
+    require!(reward_rate_bps > 0, FocusError::InvalidRewardRate);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.reward_rate_bps = reward_rate_bps;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpgradeLegacyProgram<'info> {
+    /// CHECK: a FocusProgram in its first-deployed layout, too short to load as today's.
+    /// the handler checks its owner, discriminator, size and authority
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump
+    )]
+    pub focus_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// grows a program account created before reward rates were in basis points to today's
+// layout, once. the first-deployed fields are kept, the whole-percent rate is restated in
+// bps and every field added since starts at initialize_program's default
+pub fn upgrade_legacy_program(ctx: Context<UpgradeLegacyProgram>) -> Result<()> {
+    let info = ctx.accounts.focus_program.to_account_info();
+    require!(info.owner == &crate::ID, FocusError::ProgramNotUpgradable);
+    require!(info.data_len() == LegacyFocusProgram::SPACE, FocusError::ProgramNotUpgradable);
+    let legacy = {
+        let data = info.try_borrow_data()?;
+        require!(data[..8] == *FocusProgram::DISCRIMINATOR, FocusError::ProgramNotUpgradable);
+        LegacyFocusProgram::deserialize(&mut &data[8..])?
+    };
+    require!(legacy.authority == ctx.accounts.authority.key(), FocusError::InvalidAuthority);
+
+    // the authority pays the rent for the added fields
+    let rent_shortfall = Rent::get()?.minimum_balance(FocusProgram::SPACE).saturating_sub(info.lamports());
+    if rent_shortfall > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, rent_shortfall)?;
+    }
+    info.realloc(FocusProgram::SPACE, true)?;
+
+    let program = FocusProgram::from_upgraded_legacy(&info.try_borrow_data()?)?;
+    program.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+pub fn set_min_commitment_days(ctx: Context<UpdateProgramConfig>, min_commitment_days: u8) -> Result<()> {
+    require!(min_commitment_days <= MAX_COMMITMENT_DAYS, FocusError::InvalidDayCount);
+
+    ctx.accounts.focus_program.min_commitment_days = min_commitment_days;
+
+    Ok(())
+}
+
+// sums amount_staked over the active commitments passed as remaining_accounts and,
+// when `apply` is set, overwrites total_staked with it. applying needs every active
+// commitment, so a partial set can't shrink total_staked below what is still owed;
+// the recomputed sum is returned either way
+pub fn audit_total_staked<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateProgramConfig<'info>>,
+    apply: bool,
+) -> Result<u64> {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut audited_total: u64 = 0;
+    let mut audited_count: u64 = 0;
+    for account_info in ctx.remaining_accounts.iter() {
+        require!(!seen.contains(account_info.key), FocusError::DuplicateAccount);
+        seen.push(account_info.key());
+
+        let commitment = Account::<FocusCommitment>::try_from(account_info)?;
+        if commitment.is_active {
+            audited_total = audited_total.checked_add(commitment.amount_staked).unwrap();
+            audited_count += 1;
+        }
+    }
+
+    let program = &mut ctx.accounts.focus_program;
+    msg!("total_staked recorded: {}, audited: {}", program.total_staked, audited_total);
+    if apply {
+        require!(audited_count == program.active_commitments, FocusError::IncompleteAudit);
+        program.total_staked = audited_total;
+    }
+
+    Ok(audited_total)
+}
+
+// unpausing adds the paused interval to total_downtime_secs so grant_downtime_credit
+// can extend the commitments that were affected
+pub fn set_program_paused(ctx: Context<UpdateProgramConfig>, paused: bool) -> Result<()> {
+    let program = &mut ctx.accounts.focus_program;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    if paused {
+        require!(!program.is_paused, FocusError::ProgramPaused);
+        program.is_paused = true;
+        program.paused_at = current_timestamp;
+    } else {
+        require!(program.is_paused, FocusError::ProgramNotPaused);
+        program.total_downtime_secs += current_timestamp - program.paused_at;
+        program.is_paused = false;
+        program.paused_at = 0;
+    }
+
+    Ok(())
+}
+
+// extends each active commitment passed as writable remaining_accounts by the program
+// downtime that happened since it was created or last credited
+pub fn grant_downtime_credit<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateProgramConfig<'info>>,
+) -> Result<()> {
+    let program = &ctx.accounts.focus_program;
+    require!(!program.is_paused, FocusError::ProgramPaused);
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    for account_info in ctx.remaining_accounts.iter() {
+        let mut commitment = Account::<FocusCommitment>::try_from(account_info)?;
+        if !commitment.is_active {
+            continue;
+        }
+
+        let credit = program.total_downtime_secs - commitment.downtime_credited_secs;
+        if credit > 0 {
+            commitment.shift_timeline(credit, current_timestamp);
+            commitment.downtime_credited_secs = program.total_downtime_secs;
+            commitment.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn set_first_session_reward(ctx: Context<UpdateProgramConfig>, first_session_reward: u64) -> Result<()> {
+    ctx.accounts.focus_program.first_session_reward = first_session_reward;
+
+    Ok(())
+}
+
+// limits exposure during beta; 0 removes the cap. lowering it below total_staked only
+// blocks new stake
+pub fn set_max_total_staked(ctx: Context<UpdateProgramConfig>, max_total_staked: u64) -> Result<()> {
+    ctx.accounts.focus_program.max_total_staked = max_total_staked;
+
+    Ok(())
+}
+
+pub fn set_streak_airdrop_amounts(ctx: Context<UpdateProgramConfig>, streak_airdrop_amounts: [u64; 3]) -> Result<()> {
+    ctx.accounts.focus_program.streak_airdrop_amounts = streak_airdrop_amounts;
+
+    Ok(())
+}
+
+// 0 turns referral payouts off
+pub fn set_referral_bonus(ctx: Context<UpdateProgramConfig>, referral_bonus_bps: u64) -> Result<()> {
+    require!(referral_bonus_bps <= BPS_DENOMINATOR, FocusError::InvalidBasisPoints);
+
+    ctx.accounts.focus_program.referral_bonus_bps = referral_bonus_bps;
+
+    Ok(())
+}
+
+// 0 disables the clock-vs-slot cross-check in complete_session
+pub fn set_max_clock_drift(ctx: Context<UpdateProgramConfig>, max_clock_drift_bps: u64) -> Result<()> {
+    require!(max_clock_drift_bps <= BPS_DENOMINATOR, FocusError::InvalidBasisPoints);
+
+    ctx.accounts.focus_program.max_clock_drift_bps = max_clock_drift_bps;
+
+    Ok(())
+}
+
+// replaces the tiered bonus with one that rises linearly from 0 at floor_bps to max_bonus_bps
+// of the stake at ceiling_bps, ignoring the reward rate. the floor sits between the default
+// failure threshold and the bonus tier so the curve joins the tiers below it without a jump.
+// a max_bonus_bps of 0 goes back to tiered rewards
+pub fn set_reward_curve(
+    ctx: Context<UpdateProgramConfig>,
+    floor_bps: u64,
+    ceiling_bps: u64,
+    max_bonus_bps: u64,
+) -> Result<()> {
+    if max_bonus_bps > 0 {
+        require!(
+            (DEFAULT_FAIL_THRESHOLD_BPS..=BONUS_THRESHOLD_BPS).contains(&floor_bps)
+                && floor_bps < ceiling_bps
+                && ceiling_bps <= BPS_DENOMINATOR
+                && max_bonus_bps <= BPS_DENOMINATOR,
+            FocusError::InvalidRewardCurve
+        );
+    }
+
+    let program = &mut ctx.accounts.focus_program;
+    program.curve_floor_bps = floor_bps;
+    program.curve_ceiling_bps = ceiling_bps;
+    program.curve_max_bonus_bps = max_bonus_bps;
+
+    Ok(())
+}
+
+// bounds and step size for auto_tune_reward_rate. a max_step_bps of 0 switches tuning off
+pub fn set_rate_tuning(
+    ctx: Context<UpdateProgramConfig>,
+    min_rate_bps: u64,
+    max_rate_bps: u64,
+    max_step_bps: u64,
+) -> Result<()> {
+    if max_step_bps > 0 {
+        require!(
+            min_rate_bps > 0 && min_rate_bps <= max_rate_bps && max_rate_bps <= BPS_DENOMINATOR,
+            FocusError::InvalidRateTuning
+        );
+    }
+
+    let program = &mut ctx.accounts.focus_program;
+    program.tune_min_rate_bps = min_rate_bps;
+    program.tune_max_rate_bps = max_rate_bps;
+    program.tune_max_step_bps = max_step_bps;
+
+    Ok(())
+}
+
+// escalating bonus for runs of perfect days within one term, see FocusProgram::perfect_streak_bonus
+pub fn set_perfect_streak_bonus(ctx: Context<UpdateProgramConfig>, perfect_streak_bonus_bps: u64) -> Result<()> {
+    require!(
+        perfect_streak_bonus_bps <= MAX_PERFECT_STREAK_BONUS_BPS,
+        FocusError::InvalidPerfectStreakBonus
+    );
+
+    ctx.accounts.focus_program.perfect_streak_bonus_bps = perfect_streak_bonus_bps;
+
+    Ok(())
+}
+
+// sessions completed before `early_bird_hour` in the user's local time (the commitment's day
+// offset) count toward a claim bonus of up to `bonus_bps`. an hour of 0 switches it off
+pub fn set_early_bird(ctx: Context<UpdateProgramConfig>, early_bird_hour: u8, bonus_bps: u64) -> Result<()> {
+    require!(
+        early_bird_hour < 24 && bonus_bps <= MAX_EARLY_BIRD_BONUS_BPS,
+        FocusError::InvalidEarlyBird
+    );
+
+    let program = &mut ctx.accounts.focus_program;
+    program.early_bird_hour = early_bird_hour;
+    program.early_bird_bonus_bps = bonus_bps;
+
+    Ok(())
+}
+
+// which rate claims use when reward_rate_bps or a boost moved after a commitment was created:
+// the rate at creation (protects the treasury from later raises), the current one (the default),
+// or whichever is higher (protects users from later cuts)
+pub fn set_rate_policy(ctx: Context<UpdateProgramConfig>, rate_policy: RatePolicy) -> Result<()> {
+    ctx.accounts.focus_program.rate_policy = rate_policy;
+
+    Ok(())
+}
+
+// rate-limits each user's bonus-paying claims so the reward pool can't be drained in a burst.
+// claims that only return the stake ignore it
+pub fn set_claim_cooldown(ctx: Context<UpdateProgramConfig>, claim_cooldown_secs: i64) -> Result<()> {
+    require!((0..=MAX_CLAIM_COOLDOWN_SECS).contains(&claim_cooldown_secs), FocusError::InvalidClaimCooldown);
+
+    ctx.accounts.focus_program.claim_cooldown_secs = claim_cooldown_secs;
+
+    Ok(())
+}
+
+// bounds session start times supplied by the client, e.g. to credit_missed_session. 0 only
+// accepts starts from the current second
+pub fn set_max_backdate(ctx: Context<UpdateProgramConfig>, max_backdate_secs: i64) -> Result<()> {
+    require!((0..=MAX_BACKDATE_SECS).contains(&max_backdate_secs), FocusError::InvalidMaxBackdate);
+
+    ctx.accounts.focus_program.max_backdate_secs = max_backdate_secs;
+
+    Ok(())
+}
+
+// clients often land a few seconds short of 55 minutes because of block timing
+pub fn set_duration_tolerance(ctx: Context<UpdateProgramConfig>, duration_tolerance_secs: i64) -> Result<()> {
+    require!(
+        (0..=MAX_DURATION_TOLERANCE_SECS).contains(&duration_tolerance_secs),
+        FocusError::InvalidDurationTolerance
+    );
+
+    ctx.accounts.focus_program.duration_tolerance_secs = duration_tolerance_secs;
+
+    Ok(())
+}
+
+// routes each claim payout: the bonus (when not minted), the stake returned at or above the
+// failure threshold, and the partial refund below it. a vault only holds the stake, so it
+// can't fund a bonus on top of returning it
+pub fn set_funding_sources(
+    ctx: Context<UpdateProgramConfig>,
+    bonus_source: FundingSource,
+    principal_source: FundingSource,
+    partial_refund_source: FundingSource,
+) -> Result<()> {
+    require!(bonus_source == FundingSource::RewardPool, FocusError::InvalidBonusSource);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.bonus_source = bonus_source;
+    program.principal_source = principal_source;
+    program.partial_refund_source = partial_refund_source;
+
+    Ok(())
+}
+
+// commitments that can no longer reach this success rate, even with every remaining session,
+// are failed early by refresh_daily_counter and can be claimed before their deadline. held
+// to each commitment's own fail threshold; 0 turns it off
+pub fn set_auto_fail_threshold(ctx: Context<UpdateProgramConfig>, auto_fail_threshold_bps: u64) -> Result<()> {
+    require!(auto_fail_threshold_bps <= BPS_DENOMINATOR, FocusError::InvalidFailThreshold);
+
+    ctx.accounts.focus_program.auto_fail_threshold_bps = auto_fail_threshold_bps;
+
+    Ok(())
+}
+
+// when on, claim_rewards takes the commitment's SessionRecords as remaining_accounts and only
+// credits the sessions they show, plus those whose records complete_session closed
+pub fn set_strict_claim_verification(ctx: Context<UpdateProgramConfig>, strict_claim_verification: bool) -> Result<()> {
+    ctx.accounts.focus_program.strict_claim_verification = strict_claim_verification;
+
+    Ok(())
+}
+
+// a user who claims and starts their next commitment within this many days keeps their streak,
+// the days in between don't count as missed
+pub fn set_streak_grace_days(ctx: Context<UpdateProgramConfig>, streak_grace_days: u8) -> Result<()> {
+    require!(streak_grace_days <= MAX_COMMITMENT_DAYS, FocusError::InvalidDayCount);
+
+    ctx.accounts.focus_program.streak_grace_days = streak_grace_days;
+
+    Ok(())
+}
+
+// a session ended after min_credit_secs but short of the full duration is credited for its
+// share of it, e.g. 27.5 minutes counts as half a session. 0 keeps sessions all-or-nothing
+pub fn set_min_credit_secs(ctx: Context<UpdateProgramConfig>, min_credit_secs: i64) -> Result<()> {
+    require!(
+        (0..SESSION_DURATION_SECS).contains(&min_credit_secs),
+        FocusError::InvalidMinCreditSecs
+    );
+
+    ctx.accounts.focus_program.min_credit_secs = min_credit_secs;
+
+    Ok(())
+}
+
+// sessions still need their full duration; only the wait between them shrinks
+pub fn set_catch_up_gap(ctx: Context<UpdateProgramConfig>, catch_up_gap_secs: i64) -> Result<()> {
+    require!(
+        (0..=MIN_SESSION_GAP_SECS).contains(&catch_up_gap_secs),
+        FocusError::InvalidCatchUpGap
+    );
+
+    ctx.accounts.focus_program.catch_up_gap_secs = catch_up_gap_secs;
+
+    Ok(())
+}
+
+pub fn set_max_early_penalty(ctx: Context<UpdateProgramConfig>, max_early_penalty_bps: u64) -> Result<()> {
+    require!(max_early_penalty_bps <= BPS_DENOMINATOR, FocusError::InvalidBasisPoints);
+
+    ctx.accounts.focus_program.max_early_penalty_bps = max_early_penalty_bps;
+
+    Ok(())
+}
+
+// Pubkey::default() turns the oracle off
+pub fn set_habit_oracle(ctx: Context<UpdateProgramConfig>, habit_oracle: Pubkey, oracle_weight_bps: u64) -> Result<()> {
+    require!(oracle_weight_bps <= BPS_DENOMINATOR, FocusError::InvalidBasisPoints);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.habit_oracle = habit_oracle;
+    program.oracle_weight_bps = oracle_weight_bps;
+
+    Ok(())
+}
+
+// in whole tokens so the same setting means the same stake whatever the mint's decimals
+pub fn set_min_stake(ctx: Context<UpdateProgramConfig>, min_stake_tokens: u64) -> Result<()> {
+    ctx.accounts.focus_program.min_stake_tokens = min_stake_tokens;
+
+    Ok(())
+}
+
+// in raw units: a commitment must stake min_stake_per_day for each of its days, so a long term
+// can't be backed by dust
+pub fn set_min_stake_per_day(ctx: Context<UpdateProgramConfig>, min_stake_per_day: u64) -> Result<()> {
+    ctx.accounts.focus_program.min_stake_per_day = min_stake_per_day;
+
+    Ok(())
+}
+
+// how often checkpoint_commitment may snapshot a commitment's progress. at most a full term,
+// so every commitment can take at least one; 0 switches checkpoints off
+pub fn set_checkpoint_interval(ctx: Context<UpdateProgramConfig>, checkpoint_interval_secs: i64) -> Result<()> {
+    require!(
+        (0..=MAX_COMMITMENT_DAYS as i64 * SECONDS_PER_DAY).contains(&checkpoint_interval_secs),
+        FocusError::InvalidCheckpointInterval
+    );
+
+    ctx.accounts.focus_program.checkpoint_interval_secs = checkpoint_interval_secs;
+
+    Ok(())
+}
+
+// devnet and localnet slots don't run at mainnet's 400ms, which would make complete_session
+// expect too many or too few slots for a session's duration
+pub fn set_ms_per_slot(ctx: Context<UpdateProgramConfig>, ms_per_slot: u64) -> Result<()> {
+    require!((MIN_MS_PER_SLOT..=MAX_MS_PER_SLOT).contains(&ms_per_slot), FocusError::InvalidMsPerSlot);
+
+    ctx.accounts.focus_program.ms_per_slot = ms_per_slot;
+
+    Ok(())
+}
+
+// stops staking just to park funds: a commitment with sessions on fewer distinct days gets
+// no refund at all. terms shorter than this need every day instead
+pub fn set_min_active_days(ctx: Context<UpdateProgramConfig>, min_active_days: u8) -> Result<()> {
+    require!(min_active_days <= MAX_COMMITMENT_DAYS, FocusError::InvalidDayCount);
+
+    ctx.accounts.focus_program.min_active_days = min_active_days;
+
+    Ok(())
+}
+
+// caps the rate and challenge bonuses together, e.g. 10_000 keeps a bonus within the stake
+pub fn set_bonus_cap(ctx: Context<UpdateProgramConfig>, bonus_cap_ratio_bps: u64) -> Result<()> {
+    ctx.accounts.focus_program.bonus_cap_ratio_bps = bonus_cap_ratio_bps;
+
+    Ok(())
+}
+
+pub fn set_bonus_rounding(ctx: Context<UpdateProgramConfig>, round_bonus: bool, bonus_precision: u8) -> Result<()> {
+    let program = &mut ctx.accounts.focus_program;
+    program.round_bonus = round_bonus;
+    program.bonus_precision = bonus_precision;
+
+    Ok(())
+}
+
+// one-way wind down: creation stops and every active commitment can leave with its
+// full stake through exit_on_deprecation
+pub fn deprecate_program(ctx: Context<UpdateProgramConfig>) -> Result<()> {
+    ctx.accounts.focus_program.is_deprecated = true;
+
+    Ok(())
+}
+
+pub fn set_pow_difficulty(ctx: Context<UpdateProgramConfig>, pow_difficulty: u8) -> Result<()> {
+    require!(pow_difficulty <= MAX_POW_DIFFICULTY, FocusError::InvalidPowDifficulty);
+
+    ctx.accounts.focus_program.pow_difficulty = pow_difficulty;
+
+    Ok(())
+}
+
+// off by default: sessions past the expected total are still allowed and counted
+// separately as the commitment's bonus_sessions
+pub fn set_block_extra_sessions(ctx: Context<UpdateProgramConfig>, block_extra_sessions: bool) -> Result<()> {
+    ctx.accounts.focus_program.block_extra_sessions = block_extra_sessions;
+
+    Ok(())
+}
+
+pub fn set_streak_on_start(ctx: Context<UpdateProgramConfig>, streak_on_start: bool) -> Result<()> {
+    ctx.accounts.focus_program.streak_on_start = streak_on_start;
+
+    Ok(())
+}
+
+// commitments whose whole term falls inside [start, end] earn an extra bonus on a bonus-tier
+// claim; a zero bonus turns the challenge off
+pub fn set_challenge_window(
+    ctx: Context<UpdateProgramConfig>,
+    challenge_start: i64,
+    challenge_end: i64,
+    challenge_bonus_bps: u64,
+) -> Result<()> {
+    require!(challenge_end > challenge_start, FocusError::InvalidChallengeWindow);
+    require!(challenge_bonus_bps <= BPS_DENOMINATOR, FocusError::InvalidBasisPoints);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.challenge_start = challenge_start;
+    program.challenge_end = challenge_end;
+    program.challenge_bonus_bps = challenge_bonus_bps;
+
+    Ok(())
+}
+
+// temporary promotional rate; claims before `until_timestamp` use it instead of the base rate
+pub fn boost_reward_rate(ctx: Context<UpdateProgramConfig>, new_rate_bps: u64, until_timestamp: i64) -> Result<()> {
+    require!(new_rate_bps > 0, FocusError::InvalidRewardRate);
+    require!(until_timestamp > Clock::get()?.unix_timestamp, FocusError::InvalidBoostWindow);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.boosted_rate_bps = new_rate_bps;
+    program.boost_expiry = until_timestamp;
+
+    Ok(())
+}
+
+// 0 lets sessions complete without a verify_session_events proof
+pub fn set_required_focus_ticks(ctx: Context<UpdateProgramConfig>, required_focus_ticks: u32) -> Result<()> {
+    ctx.accounts.focus_program.required_focus_ticks = required_focus_ticks;
+
+    Ok(())
+}
+
+pub fn add_authorized_caller(ctx: Context<UpdateProgramConfig>, program_id: Pubkey) -> Result<()> {
+    let program = &mut ctx.accounts.focus_program;
+    require!(!program.is_authorized_caller(&program_id), FocusError::CallerAlreadyAuthorized);
+    let count = program.authorized_caller_count as usize;
+    require!(count < MAX_AUTHORIZED_CALLERS, FocusError::CallerListFull);
+
+    program.authorized_callers[count] = program_id;
+    program.authorized_caller_count += 1;
+
+    Ok(())
+}
+
+pub fn remove_authorized_caller(ctx: Context<UpdateProgramConfig>, program_id: Pubkey) -> Result<()> {
+    let program = &mut ctx.accounts.focus_program;
+    let count = program.authorized_caller_count as usize;
+    let index = program.authorized_callers[..count]
+        .iter()
+        .position(|caller| *caller == program_id)
+        .ok_or(FocusError::CallerNotFound)?;
+
+    // keep the list packed by moving the last entry into the gap
+    program.authorized_callers[index] = program.authorized_callers[count - 1];
+    program.authorized_callers[count - 1] = Pubkey::default();
+    program.authorized_caller_count -= 1;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(constraint = treasury.mint == focus_program.focus_token_mint)]
+    pub treasury: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn set_treasury(ctx: Context<SetTreasury>) -> Result<()> {
+    ctx.accounts.focus_program.treasury = ctx.accounts.treasury.key();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetBonusMinting<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: this is a PDA that only signs mint_to CPIs, it holds no data
+    #[account(
+        seeds = [b"mint_authority"],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+// the mint's authority has to be handed to the mint_authority PDA with spl-token's
+// set_authority before minting can be switched on. switching it off makes claims pay
+// bonuses from the reward pool again
+pub fn set_bonus_minting(ctx: Context<SetBonusMinting>, mint_bonuses: bool, mint_cap_per_epoch: u64) -> Result<()> {
+    if mint_bonuses {
+        require!(
+            ctx.accounts.token_mint.mint_authority == Some(ctx.accounts.mint_authority.key()).into(),
+            FocusError::MintAuthorityNotHeld
+        );
+    }
+
+    let program = &mut ctx.accounts.focus_program;
+    program.mint_bonuses = mint_bonuses;
+    program.mint_cap_per_epoch = mint_cap_per_epoch;
+    program.mint_authority_bump = ctx.bumps.mint_authority;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetForfeitDestination<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    // only needed to set or change the charity account
+    #[account(constraint = charity.mint == focus_program.focus_token_mint)]
+    pub charity: Option<Account<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+}
+
+// the reward pool closes the loop: failed stakes fund the bonuses of those who succeed
+pub fn set_forfeit_destination(ctx: Context<SetForfeitDestination>, forfeit_destination: ForfeitDestination) -> Result<()> {
+    let program = &mut ctx.accounts.focus_program;
+    if let Some(charity) = ctx.accounts.charity.as_ref() {
+        program.charity = charity.key();
+    }
+    require!(
+        forfeit_destination != ForfeitDestination::Charity || program.charity != Pubkey::default(),
+        FocusError::CharityNotSet
+    );
+
+    program.forfeit_destination = forfeit_destination;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RelinkSession<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        constraint = session_record.commitment == from_commitment.key() @ FocusError::SessionCommitmentMismatch,
+        constraint = session_record.user == from_commitment.user @ FocusError::InvalidAuthority
+    )]
+    pub session_record: Account<'info, SessionRecord>,
+
+    #[account(mut)]
+    pub from_commitment: Account<'info, FocusCommitment>,
+
+    #[account(
+        mut,
+        constraint = to_commitment.key() != from_commitment.key() @ FocusError::DuplicateAccount,
+        constraint = to_commitment.user == from_commitment.user @ FocusError::InvalidAuthority
+    )]
+    pub to_commitment: Account<'info, FocusCommitment>,
+
+    pub authority: Signer<'info>,
+}
+
+// repoints a completed session record that a client filed under the wrong commitment of the
+// same user, moving its credit onto the right one. the record keeps its original address,
+// derived from the source commitment, so it has to be passed at that address whenever the
+// target's records are recounted. the source keeps its session ordering marks: the session
+// still happened at that time, and they only space out later sessions
+pub fn relink_session(ctx: Context<RelinkSession>) -> Result<()> {
+    let session_record = &mut ctx.accounts.session_record;
+    let from_commitment = &mut ctx.accounts.from_commitment;
+    let to_commitment = &mut ctx.accounts.to_commitment;
+    require!(session_record.completed, FocusError::SessionNotComplete);
+    require!(to_commitment.is_active, FocusError::CommitmentInactive);
+    // recounts skip sessions from before the term, so the credit would be lost again
+    let start_timestamp = session_record.start_timestamp;
+    require!(start_timestamp >= to_commitment.start_timestamp, FocusError::ImplausibleTimeline);
+    require!(
+        to_commitment.days_elapsed(start_timestamp) < to_commitment.total_days as i64,
+        FocusError::CommitmentEnded
+    );
+
+    let elapsed_secs = session_record.end_timestamp - start_timestamp;
+    let is_full_session = elapsed_secs >= SESSION_DURATION_SECS - ctx.accounts.focus_program.duration_tolerance_secs;
+    from_commitment.uncredit_session(start_timestamp, elapsed_secs, is_full_session);
+    to_commitment.credit_session(start_timestamp, elapsed_secs, is_full_session);
+    to_commitment.last_completed_start = to_commitment.last_completed_start.max(start_timestamp);
+    to_commitment.last_session_timestamp = to_commitment.last_session_timestamp.max(session_record.end_timestamp);
+    if session_record.early_bird {
+        from_commitment.early_sessions = from_commitment.early_sessions.saturating_sub(1);
+        to_commitment.early_sessions = to_commitment.early_sessions.saturating_add(1);
+    }
+    session_record.commitment = to_commitment.key();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(session_id: u64)]
+pub struct CreditMissedSession<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = SessionRecord::SPACE,
+        seeds = [b"session", commitment.key().as_ref(), &session_id.to_le_bytes()],
+        bump
+    )]
+    pub session_record: Account<'info, SessionRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", commitment.user.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// recovery for a session the user finished on-device whose complete_session transaction was
+// dropped: writes the completed record from the app's timestamps and credits it as
+// complete_session would have. the session has to start within max_backdate_secs, inside the
+// term, and after the latest completed session, so the same session can't be credited twice
+pub fn credit_missed_session(
+    ctx: Context<CreditMissedSession>,
+    session_id: u64,
+    start_timestamp: i64,
+    end_timestamp: i64,
+) -> Result<()> {
+    let program = &ctx.accounts.focus_program;
+    require!(!program.is_paused, FocusError::ProgramPaused);
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_paused, FocusError::CommitmentPaused);
+    require!(session_id == commitment.next_session_id, FocusError::InvalidSessionId);
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    check_backdated_timestamp(start_timestamp, current_timestamp, program.max_backdate_secs)?;
+    require!(
+        start_timestamp >= commitment.start_timestamp && end_timestamp <= current_timestamp,
+        FocusError::ImplausibleTimeline
+    );
+    require!(
+        commitment.days_elapsed(start_timestamp) < commitment.total_days as i64,
+        FocusError::CommitmentEnded
+    );
+    // the same gates as start_session; a commitment that only looks doomed because this
+    // session is missing isn't auto_failed yet, so it can still be credited
+    require!(
+        !(program.block_extra_sessions && commitment.sessions_exhausted()),
+        FocusError::CommitmentSessionsExhausted
+    );
+    require!(!commitment.auto_failed, FocusError::CommitmentAutoFailed);
+
+    // the same duration rules as complete_session
+    let elapsed_secs = end_timestamp - start_timestamp;
+    let is_full_session = elapsed_secs >= SESSION_DURATION_SECS - program.duration_tolerance_secs;
+    require!(
+        is_full_session || (program.min_credit_secs > 0 && elapsed_secs >= program.min_credit_secs),
+        FocusError::SessionNotComplete
+    );
+    require!(
+        commitment.last_completed_start == 0
+            || start_timestamp >= commitment.last_completed_start + SESSION_DURATION_SECS - program.duration_tolerance_secs,
+        FocusError::OverlappingSession
+    );
+
+    // a session from today still counts against today's allowance
+    commitment.roll_over_day(current_timestamp);
+    require!(
+        commitment.days_elapsed(start_timestamp) < commitment.days_completed as i64
+            || commitment.sessions_completed_today < commitment.sessions_per_day,
+        FocusError::DailySessionsCompleted
+    );
+
+    let session_record = &mut ctx.accounts.session_record;
+    session_record.user = commitment.user;
+    session_record.commitment = commitment.key();
+    session_record.bump = ctx.bumps.session_record;
+    session_record.session_number = session_id;
+    session_record.start_timestamp = start_timestamp;
+    session_record.completed = true;
+    session_record.verification_slot = Clock::get()?.slot;
+    session_record.end_timestamp = end_timestamp;
+    session_record.events_root = [0; 32];
+    session_record.verified_ticks = 0;
+    session_record.platform = 0;
+    session_record.early_bird = false;
+    session_record.rent_payer = ctx.accounts.authority.key();
+    commitment.next_session_id += 1;
+
+    commitment.last_session_timestamp = commitment.last_session_timestamp.max(end_timestamp);
+    commitment.last_completed_start = start_timestamp;
+    commitment.credit_session(start_timestamp, elapsed_secs, is_full_session);
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    // counted like complete_session: only full sessions
+    if is_full_session {
+        user_profile.total_sessions_completed += 1;
+        user_profile.record_league_session(start_timestamp)?;
+    }
+    user_profile.longest_session_secs = user_profile.longest_session_secs.max(elapsed_secs as u64);
+    user_profile.record_focus_secs(start_timestamp, elapsed_secs as u64);
+    // milestone auto-stake needs the owner's signature, so a credited session never triggers it
+    user_profile.record_active_day(start_timestamp);
+    user_profile.refresh_reputation();
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+//initialize_airdrop_pool, fund_airdrop_pool, claim_streak_airdrop
+
+#[derive(Accounts)]
+pub struct InitializeAirdropPool<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"airdrop_pool"],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority
+    )]
+    pub airdrop_pool: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize_airdrop_pool(_ctx: Context<InitializeAirdropPool>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundAirdropPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"airdrop_pool"],
+        bump
+    )]
+    pub airdrop_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == funder.key(),
+        constraint = funder_token_account.mint == airdrop_pool.mint
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// kept apart from the reward pool so airdrops can't eat into commitment bonuses
+pub fn fund_airdrop_pool(ctx: Context<FundAirdropPool>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.airdrop_pool.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+pub struct ClaimStreakAirdrop<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop_pool"],
+        bump
+    )]
+    pub airdrop_pool: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's used only as a signer for token transfers
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// one-time payout per tier once best_streak reaches STREAK_AIRDROP_TIER_DAYS[tier]
+pub fn claim_streak_airdrop(ctx: Context<ClaimStreakAirdrop>, tier: u8) -> Result<()> {
+    let tier_index = tier as usize;
+    require!(tier_index < STREAK_AIRDROP_TIER_DAYS.len(), FocusError::InvalidAirdropTier);
+
+    let amount = ctx.accounts.focus_program.streak_airdrop_amounts[tier_index];
+    require!(amount > 0, FocusError::InvalidAirdropTier);
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    require!(
+        user_profile.best_streak >= STREAK_AIRDROP_TIER_DAYS[tier_index],
+        FocusError::StreakTierNotReached
+    );
+    let tier_flag = 1u8 << tier;
+    require!(user_profile.claimed_airdrop_tiers & tier_flag == 0, FocusError::AirdropAlreadyClaimed);
+    require!(ctx.accounts.airdrop_pool.amount >= amount, FocusError::InsufficientBalance);
+
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.airdrop_pool.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)?;
+
+    user_profile.claimed_airdrop_tiers |= tier_flag;
+    user_profile.total_rewards_earned = user_profile.total_rewards_earned.checked_add(amount).unwrap();
+    user_profile.refresh_reputation();
+
+    Ok(())
+}
@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::get_instruction_relative;
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token::{self, InitializeAccount3, Mint, MintTo, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::error::*;
-//create_commitment, claim_rewards
+use crate::constants::*;
+use crate::events::*;
+use crate::instructions::session::recount_sessions;
+use crate::utils::{check_claim_funding, check_vault_funding};
+//create_commitment, create_sponsored_commitment, claim_rewards
 
 #[derive(Accounts)]
 #[instruction(commitment_id: u64)]
@@ -59,6 +66,10 @@ pub struct CreateCommitment<'info> {
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: the instructions sysvar, only needed when called via CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
 }
 
 pub fn create_commitment(
@@ -68,8 +79,25 @@ pub fn create_commitment(
     sessions_per_day: u8,
     total_days: u8,
 ) -> Result<()> {
-    require!(sessions_per_day > 0 && sessions_per_day <= 10, FocusError::InvalidSessionCount);
-    require!(total_days > 0 && total_days <= 30, FocusError::InvalidDayCount);
+    require_authorized_caller(&ctx.accounts.focus_program, ctx.accounts.instructions_sysvar.as_ref())?;
+    
+    init_commitment(
+        &mut ctx.accounts.commitment,
+        &mut ctx.accounts.focus_program,
+        ctx.accounts.user.key(),
+        ctx.accounts.user.key(),
+        ctx.bumps.commitment,
+        commitment_id,
+        amount,
+        ctx.accounts.token_mint.decimals,
+        sessions_per_day,
+        total_days,
+    )?;
+    ctx.accounts.user_profile.record_commitment(ctx.accounts.commitment.expected_sessions());
+    ctx.accounts.user_profile.bridge_commitment_gap(
+        ctx.accounts.commitment.start_timestamp,
+        ctx.accounts.focus_program.streak_grace_days,
+    );
     
     //transfer tokens to PDA vault
     let cpi_accounts = Transfer {
@@ -80,29 +108,453 @@ pub fn create_commitment(
     
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    token::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+#[instruction(commitment_id: u64)]
+pub struct CreateSponsoredCommitment<'info> {
+    #[account(
+        init,
+        payer = sponsor,
+        space = FocusCommitment::SPACE,
+        seeds = [b"commitment", performer.key().as_ref(), &commitment_id.to_le_bytes()],
+        bump
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", performer.key().as_ref()],
+        bump = performer_profile.bump
+    )]
+    pub performer_profile: Account<'info, UserProfile>,
+    
+    #[account(mut)]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    /// CHECK: only used as the commitment's user and PDA seed; their profile must exist
+    pub performer: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = sponsor_token_account.owner == sponsor.key(),
+        constraint = sponsor_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub sponsor_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        seeds = [b"vault", performer.key().as_ref(), &commitment_id.to_le_bytes()],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+    
+    /// CHECK: the instructions sysvar, only needed when called via CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+// one phase of create_commitment_batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitmentPhase {
+    pub commitment_id: u64,
+    pub amount: u64,
+    pub sessions_per_day: u8,
+    pub total_days: u8,
+}
+
+#[derive(Accounts)]
+pub struct CreateCommitmentBatch<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    
+    /// CHECK: the instructions sysvar, only needed when called via CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+}
+
+// creates a system account at the PDA `address` owned by `owner`, funded rent-exempt by `payer`
+fn create_pda_account<'info>(
+    payer: &Signer<'info>,
+    address: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    owner: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let cpi_accounts = CreateAccount {
+        from: payer.to_account_info(),
+        to: address.clone(),
+    };
+    let signer = &[signer_seeds];
+    let cpi_ctx = CpiContext::new_with_signer(system_program.to_account_info(), cpi_accounts, signer);
+    system_program::create_account(cpi_ctx, Rent::get()?.minimum_balance(space), space as u64, owner)
+}
+
+// creates the phases as back-to-back commitments of one structured program: each starts when
+// the one before it ends. every phase's commitment and vault PDA are passed as a pair in
+// remaining_accounts, and `total_amount` must equal the phases' summed stakes
+pub fn create_commitment_batch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateCommitmentBatch<'info>>,
+    phases: Vec<CommitmentPhase>,
+    total_amount: u64,
+) -> Result<()> {
+    require!(
+        !phases.is_empty() && phases.len() <= MAX_COMMITMENT_BATCH,
+        FocusError::InvalidCommitmentBatch
+    );
+    require!(ctx.remaining_accounts.len() == phases.len() * 2, FocusError::InvalidCommitmentAccounts);
+    let summed_amount = phases
+        .iter()
+        .try_fold(0u64, |sum, phase| sum.checked_add(phase.amount))
+        .ok_or(FocusError::BatchTotalMismatch)?;
+    require!(summed_amount == total_amount, FocusError::BatchTotalMismatch);
+    require_authorized_caller(&ctx.accounts.focus_program, ctx.accounts.instructions_sysvar.as_ref())?;
+    
+    let user_key = ctx.accounts.user.key();
+    let mut phase_start: Option<i64> = None;
+    for (phase, pair) in phases.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let (commitment_info, vault_info) = (&pair[0], &pair[1]);
+        let id_bytes = phase.commitment_id.to_le_bytes();
+        let (commitment_address, commitment_bump) =
+            Pubkey::find_program_address(&[b"commitment", user_key.as_ref(), &id_bytes], &crate::ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[b"vault", user_key.as_ref(), &id_bytes], &crate::ID);
+        require_keys_eq!(commitment_info.key(), commitment_address, FocusError::InvalidCommitmentAccounts);
+        require_keys_eq!(vault_info.key(), vault_address, FocusError::InvalidCommitmentAccounts);
+        
+        create_pda_account(
+            &ctx.accounts.user,
+            commitment_info,
+            &ctx.accounts.system_program,
+            &crate::ID,
+            FocusCommitment::SPACE,
+            &[b"commitment", user_key.as_ref(), &id_bytes, &[commitment_bump]],
+        )?;
+        commitment_info.try_borrow_mut_data()?[..8].copy_from_slice(FocusCommitment::DISCRIMINATOR);
+        let mut commitment = Account::<FocusCommitment>::try_from(commitment_info)?;
+        init_commitment(
+            &mut commitment,
+            &mut ctx.accounts.focus_program,
+            user_key,
+            user_key,
+            commitment_bump,
+            phase.commitment_id,
+            phase.amount,
+            ctx.accounts.token_mint.decimals,
+            phase.sessions_per_day,
+            phase.total_days,
+        )?;
+        // later phases wait for the previous phase's deadline
+        match phase_start {
+            Some(start) => commitment.start_timestamp = start,
+            None => ctx.accounts.user_profile.bridge_commitment_gap(
+                commitment.start_timestamp,
+                ctx.accounts.focus_program.streak_grace_days,
+            ),
+        }
+        phase_start = Some(
+            FocusCommitment::checked_deadline(commitment.start_timestamp, commitment.total_days)
+                .ok_or(FocusError::InvalidDayCount)?,
+        );
+        ctx.accounts.user_profile.record_commitment(commitment.expected_sessions());
+        commitment.exit(&crate::ID)?;
+        
+        create_pda_account(
+            &ctx.accounts.user,
+            vault_info,
+            &ctx.accounts.system_program,
+            &token::ID,
+            TokenAccount::LEN,
+            &[b"vault", user_key.as_ref(), &id_bytes, &[vault_bump]],
+        )?;
+        let cpi_accounts = InitializeAccount3 {
+            account: vault_info.clone(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        token::initialize_account3(CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts))?;
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: vault_info.clone(),
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token::transfer(CpiContext::new(cpi_program, cpi_accounts), phase.amount)?;
+    }
+    Ok(())
+}
+
+// the sponsor stakes on the performer's commitment: payouts go to the sponsor, and a
+// failed sponsored commitment forfeits its whole stake to the forfeit destination
+pub fn create_sponsored_commitment(
+    ctx: Context<CreateSponsoredCommitment>,
+    commitment_id: u64,
+    amount: u64,
+    sessions_per_day: u8,
+    total_days: u8,
+) -> Result<()> {
+    require!(ctx.accounts.performer.key() != ctx.accounts.sponsor.key(), FocusError::InvalidSponsor);
+    require_authorized_caller(&ctx.accounts.focus_program, ctx.accounts.instructions_sysvar.as_ref())?;
+    
+    init_commitment(
+        &mut ctx.accounts.commitment,
+        &mut ctx.accounts.focus_program,
+        ctx.accounts.performer.key(),
+        ctx.accounts.sponsor.key(),
+        ctx.bumps.commitment,
+        commitment_id,
+        amount,
+        ctx.accounts.token_mint.decimals,
+        sessions_per_day,
+        total_days,
+    )?;
+    ctx.accounts.performer_profile.record_commitment(ctx.accounts.commitment.expected_sessions());
+    ctx.accounts.performer_profile.bridge_commitment_gap(
+        ctx.accounts.commitment.start_timestamp,
+        ctx.accounts.focus_program.streak_grace_days,
+    );
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.sponsor_token_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.sponsor.to_account_info(),
+    };
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)
+}
+
+// direct calls are always allowed. under CPI the transaction's top-level instruction,
+// read from the instructions sysvar, must belong to an allowlisted program
+fn require_authorized_caller(program: &FocusProgram, instructions_sysvar: Option<&UncheckedAccount>) -> Result<()> {
+    if get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+    
+    let instructions_sysvar = instructions_sysvar.ok_or(FocusError::UnauthorizedCaller)?;
+    let top_level = get_instruction_relative(0, &instructions_sysvar.to_account_info())?;
+    require!(program.is_authorized_caller(&top_level.program_id), FocusError::UnauthorizedCaller);
+    
+    Ok(())
+}
+
+// validates the commitment parameters and fills in a freshly created commitment
+#[allow(clippy::too_many_arguments)]
+fn init_commitment(
+    commitment: &mut FocusCommitment,
+    program: &mut FocusProgram,
+    user: Pubkey,
+    sponsor: Pubkey,
+    bump: u8,
+    commitment_id: u64,
+    amount: u64,
+    mint_decimals: u8,
+    sessions_per_day: u8,
+    total_days: u8,
+) -> Result<()> {
+    require!(!program.is_paused, FocusError::ProgramPaused);
+    require!(!program.is_deprecated, FocusError::ProgramDeprecated);
+    require!(amount >= program.min_stake_amount(mint_decimals), FocusError::StakeBelowMinimum);
+    require!(sessions_per_day > 0 && sessions_per_day <= MAX_SESSIONS_PER_DAY, FocusError::InvalidSessionCount);
+    require!(total_days > 0 && total_days <= MAX_COMMITMENT_DAYS, FocusError::InvalidDayCount);
+    require!(
+        total_days >= program.min_commitment_days,
+        FocusError::CommitmentTooShort
+    );
+    require!(program.stake_covers_days(amount, total_days), FocusError::StakeTooLowForDuration);
+    require!(program.can_stake(amount), FocusError::TvlCapExceeded);
     
     //initialize commitment state
-    let commitment = &mut ctx.accounts.commitment;
-    commitment.user = ctx.accounts.user.key();
-    commitment.bump = ctx.bumps.commitment;
+    commitment.user = user;
+    commitment.sponsor = sponsor;
+    commitment.bump = bump;
     commitment.commitment_id = commitment_id;
     commitment.amount_staked = amount;
     commitment.sessions_per_day = sessions_per_day;
     commitment.total_days = total_days;
-    commitment.start_timestamp = Clock::get()?.unix_timestamp;
+    let clock = Clock::get()?;
+    require!(
+        FocusCommitment::checked_deadline(clock.unix_timestamp, total_days).is_some(),
+        FocusError::InvalidDayCount
+    );
+    commitment.start_timestamp = clock.unix_timestamp;
+    commitment.creation_slot = clock.slot;
     commitment.days_completed = 0;
     commitment.is_active = true;
     commitment.last_session_timestamp = 0;
     commitment.sessions_completed_today = 0;
+    commitment.is_paused = false;
+    commitment.paused_at = 0;
+    commitment.pause_count = 0;
+    commitment.keep_session_records = true;
+    commitment.sessions_completed = 0;
+    commitment.total_focus_secs = 0;
+    commitment.accountability_partner = Pubkey::default();
+    // only downtime from after creation is credited
+    commitment.downtime_credited_secs = program.total_downtime_secs;
+    commitment.locked_reward_rate_bps = program.reward_rate_bps;
+    commitment.rate_locked = false;
+    commitment.next_session_id = 0;
+    commitment.allowed_start_hour = 0;
+    commitment.allowed_end_hour = 0;
+    commitment.auto_stake_on_milestone = false;
+    commitment.fail_threshold_bps = DEFAULT_FAIL_THRESHOLD_BPS;
+    commitment.effective_reward_rate_used = 0;
+    commitment.revival_count = 0;
+    commitment.free_miss_used = false;
+    commitment.vault_migrated = false;
+    commitment.is_locked = false;
+    commitment.auto_renew = false;
+    commitment.bonus_sessions = 0;
+    commitment.delegate = Pubkey::default();
+    commitment.failure_success_bps = 0;
+    commitment.failure_sessions_missed = 0;
+    commitment.failure_days_short = 0;
+    commitment.skip_weekends = false;
+    commitment.day_offset_secs = 0;
+    commitment.active_day_flags = 0;
+    commitment.partial_credit_bps = 0;
+    commitment.closed_full_sessions = 0;
+    commitment.closed_partial_credit_bps = 0;
+    commitment.reminder_offset_secs = 0;
+    commitment.last_completed_start = 0;
+    commitment.auto_failed = false;
+    commitment.slot_count = 0;
+    commitment.slot_start_hours = [0; MAX_SESSIONS_PER_DAY as usize];
+    commitment.slot_end_hours = [0; MAX_SESSIONS_PER_DAY as usize];
+    commitment.perfect_day_streak = 0;
+    commitment.best_perfect_day_streak = 0;
+    commitment.last_perfect_day = 0;
+    commitment.checkpoint_count = 0;
+    commitment.checkpoints = [ProgressCheckpoint::default(); MAX_CHECKPOINTS];
+    commitment.sponsored_amount = 0;
+    commitment.early_sessions = 0;
+    // hold back the full-completion bonus so the reward pool can't be drained below it
+    commitment.reserved_bonus = amount.checked_mul(commitment.locked_reward_rate_bps).unwrap() / BPS_DENOMINATOR;
     
     //update program state
-    let program = &mut ctx.accounts.focus_program;
     program.total_staked = program.total_staked.checked_add(amount).unwrap();
+    program.active_commitments = program.active_commitments.checked_add(1).unwrap();
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability.checked_add(commitment.reserved_bonus).unwrap();
     
     Ok(())
 }
 
+// the principal returned from the vault and the bonus paid from the pool for a finished term
+pub(crate) fn term_payout(
+    commitment: &FocusCommitment,
+    program: &FocusProgram,
+    success_bps: u64,
+    reward_rate_bps: u64,
+    mint_decimals: u8,
+) -> (u64, u64) {
+    // tiers are evaluated on at most 100%: bonus sessions past the expected total (or anything
+    // else that pushes the rate over) never pay more than the top tier
+    let success_bps = success_bps.min(BPS_DENOMINATOR);
+    // too few distinct active days means nothing is refunded, whatever the session count
+    if commitment.active_days() < program.required_active_days(commitment.total_days) {
+        return (0, 0);
+    }
+    // never below the commitment's own failure threshold, so the curve can't pay a bonus on a
+    // failed term
+    let curve_floor_bps = program.curve_floor_bps.max(commitment.fail_threshold_bps);
+    let (principal, bonus) = if program.has_reward_curve() && success_bps >= curve_floor_bps {
+        //stake back + a bonus that scales with completion between the curve's floor and ceiling
+        let base_reward = commitment.amount_staked;
+        let mut bonus = program.curve_bonus(base_reward, success_bps, curve_floor_bps);
+        if success_bps >= BONUS_THRESHOLD_BPS && program.is_challenge_term(commitment.start_timestamp, commitment.deadline()) {
+            bonus += base_reward.checked_mul(program.challenge_bonus_bps).unwrap() / BPS_DENOMINATOR;
+        }
+        (base_reward, bonus)
+    } else if success_bps >= BONUS_THRESHOLD_BPS {
+        //complete reward + bonus for 90%+ completion
+        let base_reward = commitment.amount_staked;
+        let mut bonus = base_reward.checked_mul(reward_rate_bps).unwrap() / BPS_DENOMINATOR;
+        if program.is_challenge_term(commitment.start_timestamp, commitment.deadline()) {
+            bonus += base_reward.checked_mul(program.challenge_bonus_bps).unwrap() / BPS_DENOMINATOR;
+        }
+        (base_reward, bonus)
+    } else if success_bps >= commitment.fail_threshold_bps {
+        //return original stake at or above the failure threshold (75% by default)
+        (commitment.amount_staked, 0)
+    } else if commitment.is_sponsored() {
+        //a sponsor's bet is lost entirely when the performer falls below the threshold
+        (0, 0)
+    } else {
+        //partial refund below the failure threshold
+        ((commitment.amount_staked * 75) / 100, 0)
+    };
+    // a term that didn't fail also earns perfect_streak_bonus_bps for every day of its best
+    // run of perfect days, and the early-bird bonus for its morning sessions
+    let bonus = if success_bps >= commitment.fail_threshold_bps {
+        bonus
+            + program.perfect_streak_bonus(commitment.amount_staked, commitment.best_perfect_day_streak)
+            + program.early_bird_bonus(commitment.amount_staked, commitment.early_sessions, commitment.expected_sessions())
+    } else {
+        bonus
+    };
+    let bonus = program.capped_bonus(bonus, commitment.amount_staked);
+    (principal, program.rounded_bonus(bonus, mint_decimals))
+}
+
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
     #[account(
@@ -126,19 +578,80 @@ pub struct ClaimRewards<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
+    // the sponsor's account; for ordinary commitments the sponsor is the user
     #[account(
         mut,
-        constraint = user_token_account.owner == user.key(),
-        constraint = user_token_account.mint == focus_program.focus_token_mint
+        constraint = payout_token_account.owner == commitment.sponsor @ FocusError::InvalidPayoutAccount,
+        constraint = payout_token_account.mint == focus_program.focus_token_mint
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub payout_token_account: Account<'info, TokenAccount>,
     
+    /// CHECK: the stake's vault; unchecked so a closed vault fails with VaultMissing instead of
+    /// an account deserialization error, check_staked_vault validates it in the handler
     #[account(
         mut,
-        seeds = [b"vault", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        seeds = [commitment.vault_seed(), user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
         bump,
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub vault: UncheckedAccount<'info>,
+    
+    // only required when the claim pays or routes tokens through the pool: an unminted
+    // bonus, a referral bonus, a pool-funded refund or a forfeit to the pool
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+    
+    // decimals for bonus rounding, and the mint bonuses are minted from when mint_bonuses is on
+    #[account(mut, address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    
+    // only consulted when the program has a habit oracle configured
+    #[account(
+        seeds = [b"habit_attestation", user.key().as_ref()],
+        bump = habit_attestation.bump
+    )]
+    pub habit_attestation: Option<Account<'info, HabitAttestation>>,
+    
+    // optional so claims keep working on deployments that never initialized GlobalStats;
+    // without it the claim just isn't counted in the payout totals
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Option<Account<'info, GlobalStats>>,
+    
+    // only required when the user was referred and the claim reaches the bonus tier
+    #[account(
+        mut,
+        seeds = [b"user_profile", user_profile.referrer.as_ref()],
+        bump = referrer_profile.bump
+    )]
+    pub referrer_profile: Option<Account<'info, UserProfile>>,
+    
+    #[account(
+        mut,
+        constraint = referrer_token_account.owner == user_profile.referrer @ FocusError::InvalidReferrer,
+        constraint = referrer_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub referrer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // only required when a failed commitment forfeits to the treasury
+    #[account(
+        mut,
+        address = focus_program.treasury
+    )]
+    pub treasury: Option<Account<'info, TokenAccount>>,
+    
+    // only required when a failed commitment forfeits to the charity
+    #[account(
+        mut,
+        address = focus_program.charity @ FocusError::CharityNotSet
+    )]
+    pub charity: Option<Account<'info, TokenAccount>>,
     
     /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
     /// as it's used only as a signer for token transfers
@@ -148,65 +661,1335 @@ pub struct ClaimRewards<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
     
+    /// CHECK: PDA signer for mint_to, only required when the program mints bonuses
+    #[account(
+        seeds = [b"mint_authority"],
+        bump = focus_program.mint_authority_bump
+    )]
+    pub mint_authority: Option<UncheckedAccount<'info>>,
+    
     pub token_program: Program<'info, Token>,
 }
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
-    let commitment = &mut ctx.accounts.commitment;
-    let user_profile = &mut ctx.accounts.user_profile;
+// checks a finished term can be claimed now and returns its success rate, using up the
+// free miss if it made a difference
+fn claimable_success_bps(
+    commitment: &mut FocusCommitment,
+    program: &FocusProgram,
+    habit_attestation: Option<&HabitAttestation>,
+    current_timestamp: i64,
+) -> Result<u64> {
+    //verifyif the commitment has ended; an auto-failed one can be claimed right away
+    if !commitment.auto_failed {
+        let days_elapsed = commitment.days_elapsed(current_timestamp);
+        
+        require!(days_elapsed >= commitment.total_days as i64, FocusError::CommitmentNotEnded);
+        
+        // wall-clock floor, independent of how the commitment's days were counted
+        let min_elapsed = program.min_commitment_days as i64 * SECONDS_PER_DAY;
+        require!(
+            current_timestamp - commitment.start_timestamp >= min_elapsed,
+            FocusError::CommitmentTooShort
+        );
+    }
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_paused, FocusError::CommitmentPaused);
     
-    //verifyif the commitment has ended
-    let current_timestamp = Clock::get()?.unix_timestamp;
-    let day_in_seconds = 86400;
-    let days_elapsed = ((current_timestamp - commitment.start_timestamp) / day_in_seconds) as u8;
+    //calculate success rate from this commitment's own sessions
+    let success_bps = commitment.success_bps();
+    if commitment.credited_sessions() > commitment.sessions_completed {
+        commitment.free_miss_used = true;
+    }
     
-    require!(days_elapsed >= commitment.total_days, FocusError::CommitmentNotEnded);
-    require!(commitment.is_active, FocusError::CommitmentInactive);
+    Ok(program.oracle_weighted_success_bps(success_bps, habit_attestation))
+}
+
+// the vault has to still be a token account holding the stake and any gifted tokens
+fn check_staked_vault(vault_info: &AccountInfo, commitment: &FocusCommitment) -> Result<()> {
+    let vault_held = commitment.amount_staked.checked_add(commitment.sponsored_amount).unwrap();
+    check_vault_funding(vault_info.owner, &vault_info.try_borrow_data()?, vault_held)
+}
+
+// the account the configured forfeit destination points at
+fn forfeit_account<'info>(
+    program: &FocusProgram,
+    reward_pool: Option<&Account<'info, TokenAccount>>,
+    treasury: Option<&Account<'info, TokenAccount>>,
+    charity: Option<&Account<'info, TokenAccount>>,
+) -> Result<AccountInfo<'info>> {
+    Ok(match program.forfeit_destination {
+        ForfeitDestination::Treasury => treasury.ok_or(FocusError::TreasuryNotSet)?.to_account_info(),
+        ForfeitDestination::RewardPool => reward_pool.ok_or(FocusError::RewardPoolRequired)?.to_account_info(),
+        ForfeitDestination::Charity => charity.ok_or(FocusError::CharityNotSet)?.to_account_info(),
+    })
+}
+
+// pays a claim's bonus: minted through the mint_authority PDA, within the epoch cap, while
+// the program mints bonuses, otherwise transferred out of the reward pool
+#[allow(clippy::too_many_arguments)]
+fn pay_bonus<'info>(
+    program: &mut FocusProgram,
+    bonus: u64,
+    payout_token_account: &Account<'info, TokenAccount>,
+    reward_pool: Option<&Account<'info, TokenAccount>>,
+    token_mint: &Account<'info, Mint>,
+    vault_authority: &UncheckedAccount<'info>,
+    vault_authority_bump: u8,
+    mint_authority: Option<&UncheckedAccount<'info>>,
+    token_program: &Program<'info, Token>,
+) -> Result<()> {
+    if bonus == 0 {
+        return Ok(());
+    }
+    
+    if program.mint_bonuses {
+        let current_epoch = Clock::get()?.epoch;
+        require!(bonus <= program.epoch_mint_remaining(current_epoch), FocusError::EpochMintCapExceeded);
+        let mint_authority = mint_authority.ok_or(FocusError::MintAuthorityNotHeld)?;
+        let mint_seeds = &[
+            b"mint_authority".as_ref(),
+            &[program.mint_authority_bump],
+        ];
+        let mint_signer = &[&mint_seeds[..]];
+        let cpi_accounts = MintTo {
+            mint: token_mint.to_account_info(),
+            to: payout_token_account.to_account_info(),
+            authority: mint_authority.to_account_info(),
+        };
+        
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, mint_signer);
+        token::mint_to(cpi_ctx, bonus)?;
+        program.record_minted(current_epoch, bonus);
+    } else {
+        let seeds = &[
+            b"vault_authority".as_ref(),
+            &[vault_authority_bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = Transfer {
+            from: reward_pool.ok_or(FocusError::RewardPoolRequired)?.to_account_info(),
+            to: payout_token_account.to_account_info(),
+            authority: vault_authority.to_account_info(),
+        };
+        
+        let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, bonus)?;
+    }
     
-    //calculate success rate
-    let total_expected_sessions = commitment.sessions_per_day * commitment.total_days;
-    let total_completed_sessions = user_profile.total_sessions_completed;
-    let success_rate = (total_completed_sessions as f64) / (total_expected_sessions as f64);
+    Ok(())
+}
+
+// below the failure threshold the claim keeps a summary of what was missed for the frontend
+fn record_failed_term(commitment: &mut Account<FocusCommitment>, success_bps: u64) {
+    if success_bps >= commitment.fail_threshold_bps {
+        return;
+    }
+    commitment.record_failure(success_bps);
+    emit!(CommitmentFailed {
+        commitment: commitment.key(),
+        user: commitment.user,
+        success_bps: commitment.failure_success_bps,
+        sessions_missed: commitment.failure_sessions_missed,
+        days_short: commitment.failure_days_short,
+    });
+}
+
+// under strict_claim_verification the commitment's SessionRecords are passed as
+// remaining_accounts, and the claim credits no more sessions than they show
+pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    let user_profile = &mut ctx.accounts.user_profile;
     
-    //calculate reward amount
+    let current_timestamp = Clock::get()?.unix_timestamp;
     let program = &ctx.accounts.focus_program;
-    let reward_amount = if success_rate >= 0.9 {
-        //complete reward + bonus for 90%+ completion
-        let base_reward = commitment.amount_staked;
-        let bonus = (base_reward * program.reward_rate) / 100;
-        base_reward.checked_add(bonus).unwrap()
-    } else if success_rate >= 0.75 {
-        //return original stake for 75%+ completion
-        commitment.amount_staked
+    check_staked_vault(&ctx.accounts.vault, commitment)?;
+    if program.strict_claim_verification {
+        let recount = recount_sessions(commitment, program, ctx.remaining_accounts)?;
+        commitment.sessions_completed = commitment.sessions_completed.min(recount.sessions_completed);
+        commitment.partial_credit_bps = commitment.partial_credit_bps.min(recount.partial_credit_bps);
+    }
+    let success_bps = claimable_success_bps(
+        commitment,
+        program,
+        ctx.accounts.habit_attestation.as_deref(),
+        current_timestamp,
+    )?;
+    
+    //calculate the principal returned from the vault and the bonus paid from the pool
+    let reward_rate_bps = commitment.reward_rate_bps(program, current_timestamp);
+    let (principal, bonus) = term_payout(commitment, program, success_bps, reward_rate_bps, ctx.accounts.token_mint.decimals);
+    record_failed_term(commitment, success_bps);
+    // referrers are only paid for referees who reach the bonus tier, scaled by their stake
+    let referral_bonus = if success_bps >= BONUS_THRESHOLD_BPS && user_profile.referrer != Pubkey::default() {
+        commitment.amount_staked.checked_mul(program.referral_bonus_bps).unwrap() / BPS_DENOMINATOR
     } else {
-        //partial refund for less than 75% completion
-        (commitment.amount_staked * 75) / 100
+        0
     };
+    // bonuses are what a burst of claims could drain the pool of, so only bonus-paying claims
+    // wait out the cooldown; a claim returning just the stake is never held up
+    if bonus.checked_add(referral_bonus).unwrap() > 0 {
+        require!(
+            user_profile.can_claim_bonus(program.claim_cooldown_secs, current_timestamp),
+            FocusError::ClaimCooldownActive
+        );
+        user_profile.last_claim_at = current_timestamp;
+    }
+    // stake routed through the pool is swept into it before being paid out, so the vault
+    // never pays more than it holds
+    let mint_bonus = program.mint_bonuses;
+    let principal_from_pool =
+        program.refund_source(success_bps < commitment.fail_threshold_bps) == FundingSource::RewardPool;
+    let gift = commitment.gift_returned(principal);
+    let vault_held = commitment.amount_staked.checked_add(commitment.sponsored_amount).unwrap();
+    let pool_payout = if mint_bonus { referral_bonus } else { bonus.checked_add(referral_bonus).unwrap() };
+    let reward_pool = ctx.accounts.reward_pool.as_ref();
+    // fail clearly here rather than with an opaque token program error mid-transfer
+    check_claim_funding(reward_pool.map(|pool| pool.amount), pool_payout)?;
+    let forfeited = vault_held - principal - gift;
+    let reward_amount = principal.checked_add(gift).unwrap().checked_add(bonus).unwrap();
     
-    //transfer reward tokens back to user
+    //transfer reward tokens back to the sponsor
     let seeds = &[
         b"vault_authority".as_ref(),
         &[ctx.bumps.vault_authority],
     ];
     let signer = &[&seeds[..]];
     
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.vault.to_account_info(),
-        to: ctx.accounts.user_token_account.to_account_info(),
-        authority: ctx.accounts.vault_authority.to_account_info(),
-    };
+    if principal > 0 && principal_from_pool {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: reward_pool.ok_or(FocusError::RewardPoolRequired)?.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, principal)?;
+    }
     
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-    token::transfer(cpi_ctx, reward_amount)?;
+    if principal > 0 {
+        let source = if principal_from_pool {
+            reward_pool.ok_or(FocusError::RewardPoolRequired)?.to_account_info()
+        } else {
+            ctx.accounts.vault.to_account_info()
+        };
+        let cpi_accounts = Transfer {
+            from: source,
+            to: ctx.accounts.payout_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, principal)?;
+    }
+    
+    // gifted tokens never left the vault, whichever account funds the stake
+    if gift > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.payout_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, gift)?;
+    }
+    
+    if forfeited > 0 {
+        let destination = forfeit_account(
+            &ctx.accounts.focus_program,
+            reward_pool,
+            ctx.accounts.treasury.as_ref(),
+            ctx.accounts.charity.as_ref(),
+        )?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: destination,
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, forfeited)?;
+    }
+    
+    pay_bonus(
+        &mut ctx.accounts.focus_program,
+        bonus,
+        &ctx.accounts.payout_token_account,
+        reward_pool,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        ctx.bumps.vault_authority,
+        ctx.accounts.mint_authority.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+    
+    if referral_bonus > 0 {
+        let referrer_profile = ctx.accounts.referrer_profile.as_mut().ok_or(FocusError::InvalidReferrer)?;
+        let referrer_token_account = ctx.accounts.referrer_token_account.as_ref().ok_or(FocusError::InvalidReferrer)?;
+        let cpi_accounts = Transfer {
+            from: reward_pool.ok_or(FocusError::RewardPoolRequired)?.to_account_info(),
+            to: referrer_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, referral_bonus)?;
+        
+        if user_profile.qualify_referral() {
+            referrer_profile.qualified_referrals = referrer_profile.qualified_referrals.checked_add(1).unwrap();
+        }
+        referrer_profile.total_rewards_earned = referrer_profile.total_rewards_earned.checked_add(referral_bonus).unwrap();
+        referrer_profile.refresh_reputation();
+    }
     
     //update state
     commitment.is_active = false;
-    user_profile.total_rewards_earned = user_profile.total_rewards_earned.checked_add(reward_amount).unwrap();
+    commitment.effective_reward_rate_used = reward_rate_bps;
+    if let Some(global_stats) = ctx.accounts.global_stats.as_mut() {
+        global_stats.record_claim(principal.checked_add(gift).unwrap(), bonus.checked_add(referral_bonus).unwrap());
+        global_stats.record_term(success_bps);
+    }
+    user_profile.last_commitment_end = current_timestamp;
+    if !commitment.is_sponsored() {
+        user_profile.total_rewards_earned = user_profile.total_rewards_earned.checked_add(reward_amount).unwrap();
+        user_profile.refresh_reputation();
+    }
     
     //update the program state
     let program = &mut ctx.accounts.focus_program;
     program.total_staked = program.total_staked.checked_sub(commitment.amount_staked).unwrap();
+    program.active_commitments = program.active_commitments.saturating_sub(1);
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability.saturating_sub(commitment.reserved_bonus);
+    commitment.reserved_bonus = 0;
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct ClaimAllRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    // sponsored commitments pay their sponsor and have to be claimed one by one
+    #[account(
+        mut,
+        constraint = payout_token_account.owner == user.key() @ FocusError::InvalidPayoutAccount,
+        constraint = payout_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub payout_token_account: Account<'info, TokenAccount>,
+    
+    // only required when the batch pays a bonus from the pool or forfeits to it
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut, address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"habit_attestation", user.key().as_ref()],
+        bump = habit_attestation.bump
+    )]
+    pub habit_attestation: Option<Account<'info, HabitAttestation>>,
+    
+    // optional as in claim_rewards
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Option<Account<'info, GlobalStats>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", user_profile.referrer.as_ref()],
+        bump = referrer_profile.bump
+    )]
+    pub referrer_profile: Option<Account<'info, UserProfile>>,
+    
+    #[account(
+        mut,
+        constraint = referrer_token_account.owner == user_profile.referrer @ FocusError::InvalidReferrer,
+        constraint = referrer_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub referrer_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // only required when one of the commitments failed and forfeits to the treasury
+    #[account(
+        mut,
+        address = focus_program.treasury
+    )]
+    pub treasury: Option<Account<'info, TokenAccount>>,
+    
+    // only required when one of the commitments failed and forfeits to the charity
+    #[account(
+        mut,
+        address = focus_program.charity @ FocusError::CharityNotSet
+    )]
+    pub charity: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's used only as a signer for token transfers
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA signer for mint_to, only required when the program mints bonuses
+    #[account(
+        seeds = [b"mint_authority"],
+        bump = focus_program.mint_authority_bump
+    )]
+    pub mint_authority: Option<UncheckedAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+// claim_rewards for several of the user's ended commitments at once, each passed as a
+// writable (commitment, vault) pair in remaining_accounts. every commitment is settled at
+// its own tier; the bonuses are paid out together and the pool must cover all of them
+pub fn claim_all_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimAllRewards<'info>>) -> Result<()> {
+    require!(
+        !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() & 1 == 0,
+        FocusError::InvalidCommitmentAccounts
+    );
+    require!(ctx.accounts.focus_program.has_default_funding(), FocusError::CustomFundingSources);
+    require!(!ctx.accounts.focus_program.strict_claim_verification, FocusError::StrictClaimVerification);
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let user_key = ctx.accounts.user.key();
+    let has_referrer = ctx.accounts.user_profile.referrer != Pubkey::default();
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len() / 2);
+    let mut total_principal: u64 = 0;
+    let mut total_bonus: u64 = 0;
+    let mut total_referral_bonus: u64 = 0;
+    let mut released_stake: u64 = 0;
+    let mut released_liability: u64 = 0;
+    let mint_bonus = ctx.accounts.focus_program.mint_bonuses;
+    let pool_balance = ctx.accounts.reward_pool.as_ref().map(|pool| pool.amount);
+    // every commitment is settled and checked before the first transfer runs
+    let mut settled = Vec::with_capacity(ctx.remaining_accounts.len() / 2);
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let (commitment_info, vault_info) = (&pair[0], &pair[1]);
+        require!(!seen.contains(commitment_info.key), FocusError::DuplicateAccount);
+        seen.push(commitment_info.key());
+        
+        let mut commitment = Account::<FocusCommitment>::try_from(commitment_info)?;
+        require!(commitment.user == user_key, FocusError::InvalidAuthority);
+        require!(!commitment.is_sponsored(), FocusError::SponsoredCommitment);
+        let (vault_address, _) = Pubkey::find_program_address(
+            &[commitment.vault_seed(), user_key.as_ref(), &commitment.commitment_id.to_le_bytes()],
+            &crate::ID,
+        );
+        require_keys_eq!(vault_info.key(), vault_address, FocusError::InvalidCommitmentAccounts);
+        check_staked_vault(vault_info, &commitment)?;
+        
+        let program = &ctx.accounts.focus_program;
+        let success_bps = claimable_success_bps(
+            &mut commitment,
+            program,
+            ctx.accounts.habit_attestation.as_deref(),
+            current_timestamp,
+        )?;
+        let reward_rate_bps = commitment.reward_rate_bps(program, current_timestamp);
+        let (principal, bonus) = term_payout(&commitment, program, success_bps, reward_rate_bps, ctx.accounts.token_mint.decimals);
+        record_failed_term(&mut commitment, success_bps);
+        if let Some(global_stats) = ctx.accounts.global_stats.as_mut() {
+            global_stats.record_term(success_bps);
+        }
+        if success_bps >= BONUS_THRESHOLD_BPS && has_referrer {
+            let referral_bonus = commitment.amount_staked.checked_mul(program.referral_bonus_bps).unwrap() / BPS_DENOMINATOR;
+            if referral_bonus > 0 {
+                total_referral_bonus = total_referral_bonus.checked_add(referral_bonus).unwrap();
+            }
+        }
+        total_bonus = total_bonus.checked_add(bonus).unwrap();
+        
+        // the pool has to cover the bonuses of this and every earlier commitment in the batch
+        let vault_held = commitment.amount_staked.checked_add(commitment.sponsored_amount).unwrap();
+        let pool_payout = if mint_bonus {
+            total_referral_bonus
+        } else {
+            total_bonus.checked_add(total_referral_bonus).unwrap()
+        };
+        check_claim_funding(pool_balance, pool_payout)?;
+        let principal = principal.checked_add(commitment.gift_returned(principal)).unwrap();
+        let forfeited = vault_held - principal;
+        
+        total_principal = total_principal.checked_add(principal).unwrap();
+        released_stake = released_stake.checked_add(commitment.amount_staked).unwrap();
+        released_liability = released_liability.checked_add(commitment.reserved_bonus).unwrap();
+        commitment.is_active = false;
+        commitment.effective_reward_rate_used = reward_rate_bps;
+        commitment.reserved_bonus = 0;
+        settled.push((commitment, vault_info, principal, forfeited));
+    }
+    
+    for (commitment, vault_info, principal, forfeited) in settled {
+        if principal > 0 {
+            let cpi_accounts = Transfer {
+                from: vault_info.clone(),
+                to: ctx.accounts.payout_token_account.to_account_info(),
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, principal)?;
+        }
+        if forfeited > 0 {
+            let destination = forfeit_account(
+                &ctx.accounts.focus_program,
+                ctx.accounts.reward_pool.as_ref(),
+                ctx.accounts.treasury.as_ref(),
+                ctx.accounts.charity.as_ref(),
+            )?;
+            let cpi_accounts = Transfer {
+                from: vault_info.clone(),
+                to: destination,
+                authority: ctx.accounts.vault_authority.to_account_info(),
+            };
+            
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token::transfer(cpi_ctx, forfeited)?;
+        }
+        commitment.exit(&crate::ID)?;
+    }
+    
+    // the whole batch counts as one claim against the cooldown, see claim_rewards
+    if total_bonus.checked_add(total_referral_bonus).unwrap() > 0 {
+        let user_profile = &mut ctx.accounts.user_profile;
+        require!(
+            user_profile.can_claim_bonus(ctx.accounts.focus_program.claim_cooldown_secs, current_timestamp),
+            FocusError::ClaimCooldownActive
+        );
+        user_profile.last_claim_at = current_timestamp;
+    }
+    
+    pay_bonus(
+        &mut ctx.accounts.focus_program,
+        total_bonus,
+        &ctx.accounts.payout_token_account,
+        ctx.accounts.reward_pool.as_ref(),
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        ctx.bumps.vault_authority,
+        ctx.accounts.mint_authority.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+    
+    if total_referral_bonus > 0 {
+        let referrer_profile = ctx.accounts.referrer_profile.as_mut().ok_or(FocusError::InvalidReferrer)?;
+        let referrer_token_account = ctx.accounts.referrer_token_account.as_ref().ok_or(FocusError::InvalidReferrer)?;
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_pool.as_ref().ok_or(FocusError::RewardPoolRequired)?.to_account_info(),
+            to: referrer_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, total_referral_bonus)?;
+        
+        if ctx.accounts.user_profile.qualify_referral() {
+            referrer_profile.qualified_referrals = referrer_profile.qualified_referrals.checked_add(1).unwrap();
+        }
+        referrer_profile.total_rewards_earned = referrer_profile.total_rewards_earned.checked_add(total_referral_bonus).unwrap();
+        referrer_profile.refresh_reputation();
+    }
+    
+    //update state
+    if let Some(global_stats) = ctx.accounts.global_stats.as_mut() {
+        global_stats.record_claim(total_principal, total_bonus.checked_add(total_referral_bonus).unwrap());
+    }
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_rewards_earned = user_profile.total_rewards_earned
+        .checked_add(total_principal.checked_add(total_bonus).unwrap())
+        .unwrap();
+    user_profile.refresh_reputation();
+    user_profile.last_commitment_end = current_timestamp;
+    
+    let program = &mut ctx.accounts.focus_program;
+    program.total_staked = program.total_staked.checked_sub(released_stake).unwrap();
+    program.active_commitments = program.active_commitments
+        .saturating_sub((ctx.remaining_accounts.len() / 2) as u64);
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability.saturating_sub(released_liability);
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AutoRenewCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_profile", commitment.user.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    // the bonus still goes to the sponsor; only the principal stays staked
+    #[account(
+        mut,
+        constraint = payout_token_account.owner == commitment.sponsor @ FocusError::InvalidPayoutAccount,
+        constraint = payout_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub payout_token_account: Account<'info, TokenAccount>,
+    
+    // only required when the renewal pays an unminted bonus; the principal never leaves the vault
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Option<Account<'info, TokenAccount>>,
+    
+    // decimals for bonus rounding, and the mint bonuses are minted from when mint_bonuses is on
+    #[account(mut, address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    
+    // only consulted when the program has a habit oracle configured
+    #[account(
+        seeds = [b"habit_attestation", commitment.user.as_ref()],
+        bump = habit_attestation.bump
+    )]
+    pub habit_attestation: Option<Account<'info, HabitAttestation>>,
+    
+    // optional as in claim_rewards
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Option<Account<'info, GlobalStats>>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    /// CHECK: PDA signer for mint_to, only required when the program mints bonuses
+    #[account(
+        seeds = [b"mint_authority"],
+        bump = focus_program.mint_authority_bump
+    )]
+    pub mint_authority: Option<UncheckedAccount<'info>>,
+    
+    pub keeper: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// permissionless once the term has ended: pays the term's bonus like claim_rewards, then
+// starts an identical term with the principal left in the vault. a term below the
+// failure threshold isn't renewed; auto_renew is switched off so the user claims it
+pub fn auto_renew_commitment(ctx: Context<AutoRenewCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.auto_renew, FocusError::AutoRenewDisabled);
+    require!(!ctx.accounts.focus_program.is_paused, FocusError::ProgramPaused);
+    require!(!ctx.accounts.focus_program.is_deprecated, FocusError::ProgramDeprecated);
+    require!(!ctx.accounts.focus_program.strict_claim_verification, FocusError::StrictClaimVerification);
+    
+    // the same deadline, term length and success rate checks as claim_rewards
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let free_miss_used = commitment.free_miss_used;
+    let success_bps = claimable_success_bps(
+        commitment,
+        &ctx.accounts.focus_program,
+        ctx.accounts.habit_attestation.as_deref(),
+        current_timestamp,
+    )?;
+    let required_active_days = ctx.accounts.focus_program.required_active_days(commitment.total_days);
+    if success_bps < commitment.fail_threshold_bps || commitment.active_days() < required_active_days {
+        // the user's claim applies the free miss itself
+        commitment.free_miss_used = free_miss_used;
+        commitment.auto_renew = false;
+        return Ok(());
+    }
+    
+    let program = &ctx.accounts.focus_program;
+    let reward_rate_bps = commitment.reward_rate_bps(program, current_timestamp);
+    let (_, bonus) = term_payout(commitment, program, success_bps, reward_rate_bps, ctx.accounts.token_mint.decimals);
+    // a renewal pays a bonus like a claim, so it waits out the same cooldown
+    let user_profile = &mut ctx.accounts.user_profile;
+    if bonus > 0 {
+        require!(
+            user_profile.can_claim_bonus(program.claim_cooldown_secs, current_timestamp),
+            FocusError::ClaimCooldownActive
+        );
+        user_profile.last_claim_at = current_timestamp;
+    }
+    let reward_pool = ctx.accounts.reward_pool.as_ref();
+    if !program.mint_bonuses && bonus > 0 {
+        let pool_balance = reward_pool.ok_or(FocusError::RewardPoolRequired)?.amount;
+        require!(pool_balance >= bonus, FocusError::InsufficientBalance);
+    }
+    
+    pay_bonus(
+        &mut ctx.accounts.focus_program,
+        bonus,
+        &ctx.accounts.payout_token_account,
+        reward_pool,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        ctx.bumps.vault_authority,
+        ctx.accounts.mint_authority.as_ref(),
+        &ctx.accounts.token_program,
+    )?;
+    
+    if let Some(global_stats) = ctx.accounts.global_stats.as_mut() {
+        global_stats.record_claim(0, bonus);
+        global_stats.record_term(success_bps);
+    }
+    if !commitment.is_sponsored() {
+        user_profile.total_rewards_earned = user_profile.total_rewards_earned.checked_add(bonus).unwrap();
+        user_profile.refresh_reputation();
+    }
+    
+    // the new term reserves a fresh bonus at the commitment's locked rate, as at creation
+    let program = &mut ctx.accounts.focus_program;
+    let reserved_bonus = commitment.amount_staked.checked_mul(commitment.locked_reward_rate_bps).unwrap() / BPS_DENOMINATOR;
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability
+        .saturating_sub(commitment.reserved_bonus)
+        .checked_add(reserved_bonus)
+        .unwrap();
+    commitment.reserved_bonus = reserved_bonus;
+    
+    commitment.restart_term(current_timestamp, program.total_downtime_secs);
+    commitment.free_miss_used = false;
+    commitment.pause_count = 0;
+    commitment.revival_count = 0;
+    ctx.accounts.user_profile.record_commitment(commitment.expected_sessions());
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExitOnDeprecation<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        constraint = focus_program.is_deprecated @ FocusError::ProgramNotDeprecated
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    pub user: Signer<'info>,
+    
+    // matched to the vault rather than the program mint so unmigrated vaults can still leave
+    #[account(
+        mut,
+        constraint = payout_token_account.owner == commitment.sponsor @ FocusError::InvalidPayoutAccount,
+        constraint = payout_token_account.mint == vault.mint
+    )]
+    pub payout_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [commitment.vault_seed(), user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+// returns the full principal to the sponsor, whatever the completion rate, once the
+// program is deprecated. works while paused so nobody is trapped during a shutdown
+pub fn exit_on_deprecation(ctx: Context<ExitOnDeprecation>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    
+    let principal = commitment.amount_staked;
+    let payout = principal.checked_add(commitment.sponsored_amount).unwrap();
+    require!(ctx.accounts.vault.amount >= payout, FocusError::InsufficientVaultBalance);
+    
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.vault.to_account_info(),
+        to: ctx.accounts.payout_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, payout)?;
+    
+    commitment.is_active = false;
+    
+    let program = &mut ctx.accounts.focus_program;
+    program.total_staked = program.total_staked.checked_sub(principal).unwrap();
+    program.active_commitments = program.active_commitments.saturating_sub(1);
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability.saturating_sub(commitment.reserved_bonus);
+    commitment.reserved_bonus = 0;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [commitment.vault_seed(), user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        address = focus_program.treasury @ FocusError::TreasuryNotSet
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+// quits before the deadline. the penalty, sent to the treasury, is max_early_penalty_bps
+// of the stake scaled by the share of the term still remaining, so cancelling on day 28
+// of 30 costs far less than cancelling on day 1
+pub fn cancel_commitment(ctx: Context<CancelCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    // a sponsor's stake isn't the performer's to withdraw
+    require!(!commitment.is_sponsored(), FocusError::SponsoredCommitment);
+    
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let days_remaining = commitment.total_days as i64 - commitment.days_elapsed(current_timestamp).max(0);
+    require!(days_remaining > 0, FocusError::CommitmentEnded);
+    
+    let penalty = ctx.accounts.focus_program.early_cancel_penalty(commitment.amount_staked, days_remaining as u64, commitment.total_days);
+    let refund = commitment.amount_staked - penalty;
+    let gift = commitment.gift_returned(refund);
+    let vault_held = commitment.amount_staked.checked_add(commitment.sponsored_amount).unwrap();
+    require!(ctx.accounts.vault.amount >= vault_held, FocusError::InsufficientVaultBalance);
+    // a gift is refunded in the same share as the stake, the rest goes with the penalty
+    let refund = refund.checked_add(gift).unwrap();
+    let penalty = vault_held - refund;
+    
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+    
+    if refund > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, refund)?;
+    }
+    
+    if penalty > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token::transfer(cpi_ctx, penalty)?;
+    }
+    
+    commitment.is_active = false;
+    
+    let program = &mut ctx.accounts.focus_program;
+    program.total_staked = program.total_staked.checked_sub(commitment.amount_staked).unwrap();
+    program.active_commitments = program.active_commitments.saturating_sub(1);
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability.saturating_sub(commitment.reserved_bonus);
+    commitment.reserved_bonus = 0;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    pub user: Signer<'info>,
+}
+
+// when disabled, complete_session closes each SessionRecord and refunds its rent. the
+// commitment keeps a count of the closed records' credit for strict_claim_verification
+pub fn set_keep_session_records(ctx: Context<UpdateCommitment>, keep_session_records: bool) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    commitment.keep_session_records = keep_session_records;
+
+    Ok(())
+}
+
+// opts out of promotions and later rate changes, claiming at the rate from creation
+pub fn lock_reward_rate(ctx: Context<UpdateCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    commitment.rate_locked = true;
+
+    Ok(())
+}
+
+// equal hours clear the window; a start hour after the end hour wraps past midnight
+pub fn set_focus_window(ctx: Context<UpdateCommitment>, allowed_start_hour: u8, allowed_end_hour: u8) -> Result<()> {
+    require!(allowed_start_hour < 24 && allowed_end_hour < 24, FocusError::InvalidFocusWindow);
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    commitment.allowed_start_hour = allowed_start_hour;
+    commitment.allowed_end_hour = allowed_end_hour;
+
+    Ok(())
+}
+
+// one [start, end) UTC hour window per daily session, completed in order. empty lists turn
+// the slots off
+pub fn set_session_slots(ctx: Context<UpdateCommitment>, slot_start_hours: Vec<u8>, slot_end_hours: Vec<u8>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    require!(slot_start_hours.len() == slot_end_hours.len(), FocusError::InvalidSessionSlots);
+    require!(
+        slot_start_hours.is_empty() || slot_start_hours.len() == commitment.sessions_per_day as usize,
+        FocusError::InvalidSessionSlots
+    );
+    for (start_hour, end_hour) in slot_start_hours.iter().zip(slot_end_hours.iter()) {
+        require!(*start_hour < 24 && *end_hour < 24, FocusError::InvalidFocusWindow);
+        require!(start_hour != end_hour, FocusError::InvalidSessionSlots);
+    }
+
+    commitment.slot_count = slot_start_hours.len() as u8;
+    commitment.slot_start_hours = [0; MAX_SESSIONS_PER_DAY as usize];
+    commitment.slot_end_hours = [0; MAX_SESSIONS_PER_DAY as usize];
+    commitment.slot_start_hours[..slot_start_hours.len()].copy_from_slice(&slot_start_hours);
+    commitment.slot_end_hours[..slot_end_hours.len()].copy_from_slice(&slot_end_hours);
+
+    Ok(())
+}
+
+// lets auto_renew_commitment roll the stake into a new term when this one ends
+pub fn set_auto_renew(ctx: Context<UpdateCommitment>, auto_renew: bool) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    commitment.auto_renew = auto_renew;
+
+    Ok(())
+}
+
+// read by off-chain notifiers, which nudge the user this long before each commitment
+// day ends; the program itself never acts on it
+pub fn set_reminder_offset(ctx: Context<UpdateCommitment>, reminder_offset_secs: u32) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    require!((reminder_offset_secs as i64) < SECONDS_PER_DAY, FocusError::InvalidReminderOffset);
+
+    commitment.reminder_offset_secs = reminder_offset_secs;
+
+    Ok(())
+}
+
+// a key, e.g. on a phone without the main wallet, that may start and complete sessions
+// for this commitment. everything else stays owner-only; Pubkey::default() revokes it
+pub fn set_session_delegate(ctx: Context<UpdateCommitment>, delegate: Pubkey) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    commitment.delegate = delegate;
+
+    Ok(())
+}
+
+// one-way: a locked commitment keeps its terms until it is claimed; sessions, pauses and
+// claims work as usual
+pub fn lock_commitment(ctx: Context<UpdateCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+
+    commitment.is_locked = true;
+
+    Ok(())
+}
+
+// on each streak milestone complete_session moves part of the user's earned rewards into this stake
+pub fn set_auto_stake_on_milestone(ctx: Context<UpdateCommitment>, auto_stake_on_milestone: bool) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    // the performer's rewards shouldn't top up someone else's bet
+    require!(!commitment.is_sponsored(), FocusError::SponsoredCommitment);
+
+    commitment.auto_stake_on_milestone = auto_stake_on_milestone;
+
+    Ok(())
+}
+
+// stricter users can treat more than the default 25% shortfall as failure; fixed once
+// the first session has started
+pub fn set_fail_threshold(ctx: Context<UpdateCommitment>, fail_threshold_bps: u64) -> Result<()> {
+    require!(
+        (DEFAULT_FAIL_THRESHOLD_BPS..=BONUS_THRESHOLD_BPS).contains(&fail_threshold_bps),
+        FocusError::InvalidFailThreshold
+    );
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    require!(commitment.next_session_id == 0, FocusError::CommitmentAlreadyStarted);
+
+    commitment.fail_threshold_bps = fail_threshold_bps;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetSkipWeekends<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    // holds the expected sessions counted at creation, which skipping weekends changes
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub user: Signer<'info>,
+}
+
+// weekend sessions are still allowed, they just aren't expected. any three days hold a
+// weekday, so shorter terms can't opt in and end up expecting nothing
+pub fn set_skip_weekends(ctx: Context<SetSkipWeekends>, skip_weekends: bool, day_offset_secs: i32) -> Result<()> {
+    require!(
+        (-MAX_DAY_OFFSET_SECS..=MAX_DAY_OFFSET_SECS).contains(&day_offset_secs),
+        FocusError::InvalidTimezoneOffset
+    );
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+    require!(commitment.next_session_id == 0, FocusError::CommitmentAlreadyStarted);
+    require!(!skip_weekends || commitment.total_days >= 3, FocusError::InvalidDayCount);
+
+    let previous_expected = commitment.expected_sessions();
+    commitment.skip_weekends = skip_weekends;
+    commitment.day_offset_secs = day_offset_secs;
+    ctx.accounts.user_profile.restate_commitment(previous_expected, commitment.expected_sessions());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReviveCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Option<Account<'info, GlobalStats>>,
+
+    pub user: Signer<'info>,
+}
+
+// restarts an unclaimed commitment shortly after its deadline with a fresh term and the
+// same stake, instead of taking the partial refund. session ids keep counting so old
+// SessionRecord PDAs can't collide with new ones
+pub fn revive_commitment(ctx: Context<ReviveCommitment>) -> Result<()> {
+    require!(!ctx.accounts.focus_program.is_paused, FocusError::ProgramPaused);
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_paused, FocusError::CommitmentPaused);
+    require!(!commitment.is_locked, FocusError::CommitmentLocked);
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let deadline = commitment.deadline();
+    require!(current_timestamp >= deadline, FocusError::CommitmentNotEnded);
+    require!(current_timestamp - deadline <= REVIVAL_WINDOW_SECS, FocusError::RevivalWindowClosed);
+    require!(commitment.revival_count < MAX_REVIVALS_PER_COMMITMENT, FocusError::RevivalLimitReached);
+
+    // the abandoned term's success counts toward the averages, the new term's sessions
+    // toward the all-time completion rate
+    if let Some(global_stats) = ctx.accounts.global_stats.as_mut() {
+        global_stats.record_term(commitment.success_bps());
+    }
+    commitment.restart_term(current_timestamp, ctx.accounts.focus_program.total_downtime_secs);
+    commitment.revival_count += 1;
+    ctx.accounts.user_profile.record_commitment(commitment.expected_sessions());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckpointCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    pub keeper: Signer<'info>,
+}
+
+// permissionless: records the commitment's progress once each checkpoint interval has passed,
+// counting expected sessions only over the commitment days that have ended
+pub fn checkpoint_commitment(ctx: Context<CheckpointCommitment>) -> Result<()> {
+    let interval_secs = ctx.accounts.focus_program.checkpoint_interval_secs;
+    require!(interval_secs > 0, FocusError::CheckpointsDisabled);
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    let index = commitment.checkpoint_count as usize;
+    require!(index < MAX_CHECKPOINTS, FocusError::CheckpointHistoryFull);
+    
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    require!(
+        current_timestamp >= commitment.next_checkpoint_at(interval_secs),
+        FocusError::CheckpointNotDue
+    );
+    
+    commitment.checkpoints[index] = ProgressCheckpoint {
+        timestamp: current_timestamp,
+        sessions_completed: commitment.sessions_completed,
+        expected_sessions: commitment.expected_sessions_in(commitment.days_elapsed(current_timestamp)),
+    };
+    commitment.checkpoint_count += 1;
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SponsorCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
+    #[account(
+        mut,
+        seeds = [commitment.vault_seed(), commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = donor_token_account.owner == donor.key() @ FocusError::InvalidAuthority,
+        constraint = donor_token_account.mint == vault.mint
+    )]
+    pub donor_token_account: Account<'info, TokenAccount>,
+    
+    pub donor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// lets anyone gift tokens into an active commitment's vault. the gift is kept apart from the
+// stake in sponsored_amount: it earns no bonus and isn't counted in total_staked, and the claim
+// pays it out in the same proportion as the stake. sponsored commitments already pay a
+// third party, so they don't take gifts
+pub fn sponsor_commitment(ctx: Context<SponsorCommitment>, amount: u64) -> Result<()> {
+    require!(amount > 0, FocusError::InvalidGiftAmount);
+    require!(!ctx.accounts.focus_program.is_paused, FocusError::ProgramPaused);
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_sponsored(), FocusError::SponsoredCommitment);
+    
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.donor_token_account.to_account_info(),
+        to: ctx.accounts.vault.to_account_info(),
+        authority: ctx.accounts.donor.to_account_info(),
+    };
+    
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)?;
+    
+    commitment.sponsored_amount = commitment.sponsored_amount.checked_add(amount).unwrap();
+    
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zeroed<T: AccountDeserialize>(space: usize) -> T {
+        T::try_deserialize_unchecked(&mut &vec![0u8; space][..]).unwrap()
+    }
+
+    #[test]
+    fn over_completion_pays_like_a_perfect_term() {
+        let mut program: FocusProgram = zeroed(FocusProgram::SPACE);
+        program.apply_default_config();
+        let mut commitment: FocusCommitment = zeroed(FocusCommitment::SPACE);
+        commitment.amount_staked = 1_000_000;
+        commitment.sessions_per_day = 1;
+        commitment.total_days = 2;
+        commitment.fail_threshold_bps = DEFAULT_FAIL_THRESHOLD_BPS;
+
+        let perfect = term_payout(&commitment, &program, BPS_DENOMINATOR, 1_250, 6);
+        assert_eq!(perfect, (1_000_000, 125_000));
+        assert_eq!(term_payout(&commitment, &program, 15_000, 1_250, 6), perfect);
+
+        // the reward curve is evaluated on the same clamped rate
+        program.curve_floor_bps = 7_500;
+        program.curve_ceiling_bps = BPS_DENOMINATOR;
+        program.curve_max_bonus_bps = 2_000;
+        let perfect = term_payout(&commitment, &program, BPS_DENOMINATOR, 1_250, 6);
+        assert_eq!(perfect, (1_000_000, 200_000));
+        assert_eq!(term_payout(&commitment, &program, 15_000, 1_250, 6), perfect);
+    }
+}
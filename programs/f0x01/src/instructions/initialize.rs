@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 // use anchor_spl::token::{Mint, Token};
 use anchor_spl::token::{Mint, Token};
 use crate::state::*;
-// use crate::error::*;
+use crate::error::*;
 
 #[derive(Accounts)]
 pub struct InitializeProgram<'info> {
@@ -22,14 +22,18 @@ pub struct InitializeProgram<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn initialize_program(ctx: Context<InitializeProgram>, reward_rate: u64) -> Result<()> {
+pub fn initialize_program(ctx: Context<InitializeProgram>, reward_rate_bps: u64) -> Result<()> {
+    // a zero rate would silently pay 90%+ completers only their principal back
+    require!(reward_rate_bps > 0, FocusError::InvalidRewardRate);
+
     let program = &mut ctx.accounts.focus_program;
     program.authority = ctx.accounts.authority.key();
     program.bump = ctx.bumps.focus_program;
     program.total_users = 0;
     program.total_staked = 0;
-    program.reward_rate = reward_rate;
+    program.reward_rate_bps = reward_rate_bps;
     program.focus_token_mint = ctx.accounts.focus_token_mint.key();
+    program.apply_default_config();
     
     Ok(())
 }
\ No newline at end of file
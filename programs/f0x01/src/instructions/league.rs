@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+//reset_weekly_stats
+
+#[derive(Accounts)]
+pub struct ResetWeeklyStats<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user_profile.user.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    // keyed by the week being archived, so the same week can never be archived twice
+    #[account(
+        init,
+        payer = payer,
+        space = WeeklyLeagueEntry::SPACE,
+        seeds = [b"league_entry", user_profile.user.as_ref(), &user_profile.league_week.to_le_bytes()],
+        bump
+    )]
+    pub league_entry: Account<'info, WeeklyLeagueEntry>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// permissionless so a league keeper can roll every profile over once the week ends
+pub fn reset_weekly_stats(ctx: Context<ResetWeeklyStats>) -> Result<()> {
+    let current_week = WeeklyLeagueEntry::week_of(Clock::get()?.unix_timestamp);
+    let user_profile = &mut ctx.accounts.user_profile;
+    require!(current_week > user_profile.league_week, FocusError::LeagueWeekNotEnded);
+
+    let league_entry = &mut ctx.accounts.league_entry;
+    league_entry.user = user_profile.user;
+    league_entry.bump = ctx.bumps.league_entry;
+    league_entry.week = user_profile.league_week;
+    league_entry.sessions_completed = user_profile.weekly_sessions;
+
+    user_profile.weekly_sessions = 0;
+    user_profile.league_week = current_week;
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+//migrate_mint, migrate_vault
+
+#[derive(Accounts)]
+pub struct MigrateMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub legacy_mint: Account<'info, Mint>,
+
+    #[account(constraint = new_mint.key() != legacy_mint.key() @ FocusError::InvalidMigrationMint)]
+    pub new_mint: Account<'info, Mint>,
+
+    // collects the old tokens swapped out of commitment vaults
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"legacy_reserve"],
+        bump,
+        token::mint = legacy_mint,
+        token::authority = vault_authority
+    )]
+    pub legacy_reserve: Account<'info, TokenAccount>,
+
+    // new tokens the authority funds up front, paid into migrated vaults
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"migration_reserve"],
+        bump,
+        token::mint = new_mint,
+        token::authority = vault_authority
+    )]
+    pub migration_reserve: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// switches the program to a relaunched token. it can only happen once, since the
+// reserves are fixed PDAs; existing vaults move over one at a time with migrate_vault
+pub fn migrate_mint(ctx: Context<MigrateMint>, ratio_bps: u64) -> Result<()> {
+    require!(ratio_bps > 0, FocusError::InvalidMigrationRatio);
+
+    let program = &mut ctx.accounts.focus_program;
+    program.legacy_token_mint = ctx.accounts.legacy_mint.key();
+    program.focus_token_mint = ctx.accounts.new_mint.key();
+    program.mint_migration_ratio_bps = ratio_bps;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        constraint = focus_program.legacy_token_mint != Pubkey::default() @ FocusError::MintMigrationNotStarted
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority,
+        constraint = !commitment.vault_migrated @ FocusError::VaultAlreadyMigrated
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    // commitments created after migrate_mint already hold the new token here
+    #[account(
+        mut,
+        seeds = [b"vault", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+        constraint = vault.mint == focus_program.legacy_token_mint @ FocusError::VaultAlreadyMigrated
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"migrated_vault", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+        token::mint = new_mint,
+        token::authority = vault_authority
+    )]
+    pub migrated_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"legacy_reserve"],
+        bump
+    )]
+    pub legacy_reserve: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"migration_reserve"],
+        bump
+    )]
+    pub migration_reserve: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub new_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// swaps a commitment's whole vault at the configured ratio in one instruction, so a
+// vault is never left holding a mix of old and new tokens
+pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+    let ratio_bps = ctx.accounts.focus_program.mint_migration_ratio_bps;
+    let legacy_amount = ctx.accounts.vault.amount;
+    let migrated_amount = legacy_amount.checked_mul(ratio_bps).unwrap() / BPS_DENOMINATOR;
+    require!(
+        ctx.accounts.migration_reserve.amount >= migrated_amount,
+        FocusError::InsufficientBalance
+    );
+
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+
+    if legacy_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.legacy_reserve.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, legacy_amount)?;
+    }
+
+    if migrated_amount > 0 {
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.migration_reserve.to_account_info(),
+            to: ctx.accounts.migrated_vault.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer);
+        token::transfer(cpi_ctx, migrated_amount)?;
+    }
+
+    // restate the stake and its reserved bonus in the new token
+    let commitment = &mut ctx.accounts.commitment;
+    let program = &mut ctx.accounts.focus_program;
+    let migrated_stake = commitment.amount_staked.checked_mul(ratio_bps).unwrap() / BPS_DENOMINATOR;
+    let migrated_bonus = commitment.reserved_bonus.checked_mul(ratio_bps).unwrap() / BPS_DENOMINATOR;
+    program.total_staked = program.total_staked
+        .checked_sub(commitment.amount_staked)
+        .unwrap()
+        .checked_add(migrated_stake)
+        .unwrap();
+    program.outstanding_bonus_liability = program.outstanding_bonus_liability
+        .saturating_sub(commitment.reserved_bonus)
+        .checked_add(migrated_bonus)
+        .unwrap();
+    commitment.amount_staked = migrated_stake;
+    commitment.reserved_bonus = migrated_bonus;
+    commitment.sponsored_amount = commitment.sponsored_amount.checked_mul(ratio_bps).unwrap() / BPS_DENOMINATOR;
+    commitment.vault_migrated = true;
+
+    Ok(())
+}
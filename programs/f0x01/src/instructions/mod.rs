@@ -1,6 +1,9 @@
 pub mod initialize;
 pub use initialize::*;
 
+pub mod admin;
+pub use admin::*;
+
 pub mod user;
 pub use user::*;
 
@@ -8,4 +11,31 @@ pub mod commitment;
 pub use commitment::*;
 
 pub mod session;
-pub use session::*;
\ No newline at end of file
+pub use session::*;
+
+pub mod pause;
+pub use pause::*;
+
+pub mod views;
+pub use views::*;
+
+pub mod partner;
+pub use partner::*;
+
+pub mod reward_pool;
+pub use reward_pool::*;
+
+pub mod stats;
+pub use stats::*;
+
+pub mod airdrop;
+pub use airdrop::*;
+
+pub mod league;
+pub use league::*;
+
+pub mod migration;
+pub use migration::*;
+
+pub mod oracle;
+pub use oracle::*;
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+//post_habit_attestation
+
+#[derive(Accounts)]
+pub struct PostHabitAttestation<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        constraint = focus_program.habit_oracle != Pubkey::default()
+            && focus_program.habit_oracle == oracle.key() @ FocusError::InvalidOracle
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = HabitAttestation::SPACE,
+        seeds = [b"habit_attestation", user.key().as_ref()],
+        bump
+    )]
+    pub habit_attestation: Account<'info, HabitAttestation>,
+
+    /// CHECK: only used as the attestation's seed; the user doesn't sign oracle updates
+    pub user: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub oracle: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// overwrites the user's attestation with the oracle's latest totals
+pub fn post_habit_attestation(
+    ctx: Context<PostHabitAttestation>,
+    days_attested: u16,
+    days_completed: u16,
+) -> Result<()> {
+    require!(days_completed <= days_attested, FocusError::InvalidAttestation);
+
+    let habit_attestation = &mut ctx.accounts.habit_attestation;
+    habit_attestation.user = ctx.accounts.user.key();
+    habit_attestation.bump = ctx.bumps.habit_attestation;
+    habit_attestation.oracle = ctx.accounts.oracle.key();
+    habit_attestation.days_attested = days_attested;
+    habit_attestation.days_completed = days_completed;
+    habit_attestation.updated_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
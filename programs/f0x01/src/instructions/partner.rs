@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+//register_for_partner, match_partner
+
+#[derive(Accounts)]
+pub struct RegisterForPartner<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = PartnerListing::SPACE,
+        seeds = [b"partner_listing", user.key().as_ref()],
+        bump
+    )]
+    pub partner_listing: Account<'info, PartnerListing>,
+
+    #[account(
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_for_partner(ctx: Context<RegisterForPartner>, timezone_offset_mins: i16) -> Result<()> {
+    require!((-14 * 60..=14 * 60).contains(&timezone_offset_mins), FocusError::InvalidTimezoneOffset);
+
+    let commitment = &ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(commitment.accountability_partner == Pubkey::default(), FocusError::AlreadyPartnered);
+
+    let listing = &mut ctx.accounts.partner_listing;
+    listing.user = ctx.accounts.user.key();
+    listing.bump = ctx.bumps.partner_listing;
+    listing.commitment = commitment.key();
+    listing.daily_target = commitment.sessions_per_day;
+    listing.timezone_offset_mins = timezone_offset_mins;
+    listing.listed_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MatchPartner<'info> {
+    #[account(
+        mut,
+        seeds = [b"partner_listing", listing_a.user.as_ref()],
+        bump = listing_a.bump,
+        close = user_a
+    )]
+    pub listing_a: Account<'info, PartnerListing>,
+
+    #[account(
+        mut,
+        seeds = [b"partner_listing", listing_b.user.as_ref()],
+        bump = listing_b.bump,
+        close = user_b,
+        constraint = listing_a.user != listing_b.user @ FocusError::IncompatiblePartners
+    )]
+    pub listing_b: Account<'info, PartnerListing>,
+
+    #[account(mut, address = listing_a.commitment)]
+    pub commitment_a: Account<'info, FocusCommitment>,
+
+    #[account(mut, address = listing_b.commitment)]
+    pub commitment_b: Account<'info, FocusCommitment>,
+
+    /// CHECK: only receives the rent of listing_a, pinned to the listing's owner
+    #[account(mut, address = listing_a.user)]
+    pub user_a: UncheckedAccount<'info>,
+
+    /// CHECK: only receives the rent of listing_b, pinned to the listing's owner
+    #[account(mut, address = listing_b.user)]
+    pub user_b: UncheckedAccount<'info>,
+
+    // anyone (either registrant or a matching keeper) can pair two opted-in listings
+    pub matcher: Signer<'info>,
+}
+
+pub fn match_partner(ctx: Context<MatchPartner>) -> Result<()> {
+    let listing_a = &ctx.accounts.listing_a;
+    let listing_b = &ctx.accounts.listing_b;
+
+    let timezone_gap = (listing_a.timezone_offset_mins - listing_b.timezone_offset_mins).abs();
+    require!(
+        listing_a.daily_target == listing_b.daily_target && timezone_gap <= MAX_PARTNER_TIMEZONE_GAP_MINS,
+        FocusError::IncompatiblePartners
+    );
+
+    let commitment_a_key = ctx.accounts.commitment_a.key();
+    let commitment_b_key = ctx.accounts.commitment_b.key();
+    let commitment_a = &mut ctx.accounts.commitment_a;
+    let commitment_b = &mut ctx.accounts.commitment_b;
+    require!(commitment_a.is_active && commitment_b.is_active, FocusError::CommitmentInactive);
+    require!(
+        commitment_a.accountability_partner == Pubkey::default()
+            && commitment_b.accountability_partner == Pubkey::default(),
+        FocusError::AlreadyPartnered
+    );
+
+    // link the commitments; both listings are closed, removing the users from the pool
+    commitment_a.accountability_partner = commitment_b_key;
+    commitment_b.accountability_partner = commitment_a_key;
+
+    Ok(())
+}
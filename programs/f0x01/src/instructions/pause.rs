@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+//pause_commitment, resume_commitment, pause_all_commitments
+
+#[derive(Accounts)]
+pub struct PauseCommitment<'info> {
+    #[account(
+        mut,
+        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+
+    pub user: Signer<'info>,
+}
+
+fn pause(commitment: &mut FocusCommitment, current_timestamp: i64) -> Result<()> {
+    require!(commitment.pause_count < MAX_PAUSES_PER_COMMITMENT, FocusError::PauseLimitReached);
+
+    commitment.is_paused = true;
+    commitment.paused_at = current_timestamp;
+    commitment.pause_count += 1;
+
+    Ok(())
+}
+
+pub fn pause_commitment(ctx: Context<PauseCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_paused, FocusError::CommitmentPaused);
+
+    pause(commitment, Clock::get()?.unix_timestamp)
+}
+
+pub fn resume_commitment(ctx: Context<PauseCommitment>) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_paused, FocusError::CommitmentNotPaused);
+
+    // the time spent paused is added back onto the commitment's deadline
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let paused_for = current_timestamp - commitment.paused_at;
+    commitment.shift_timeline(paused_for, current_timestamp);
+    commitment.is_paused = false;
+    commitment.paused_at = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PauseAllCommitments<'info> {
+    pub user: Signer<'info>,
+}
+
+// commitments are passed as writable remaining_accounts; inactive or already
+// paused ones are skipped, and any active commitment that is out of pauses
+// fails the whole transaction so nothing is left half-paused
+pub fn pause_all_commitments<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PauseAllCommitments<'info>>,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let user = ctx.accounts.user.key();
+
+    for account_info in ctx.remaining_accounts.iter() {
+        let mut commitment = Account::<FocusCommitment>::try_from(account_info)?;
+        require!(commitment.user == user, FocusError::InvalidAuthority);
+
+        if !commitment.is_active || commitment.is_paused {
+            continue;
+        }
+
+        pause(&mut commitment, current_timestamp)?;
+        commitment.exit(&crate::ID)?;
+    }
+
+    Ok(())
+}
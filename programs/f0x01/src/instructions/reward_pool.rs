@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::error::*;
+//initialize_reward_pool, fund_reward_pool, claim_first_session_reward, drain_reward_pool_dust
+
+#[derive(Accounts)]
+pub struct InitializeRewardPool<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_pool"],
+        bump,
+        token::mint = token_mint,
+        token::authority = vault_authority
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's not expected to be a deserialized account with specific data
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize_reward_pool(_ctx: Context<InitializeRewardPool>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == funder.key(),
+        constraint = funder_token_account.mint == reward_pool.mint
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// anyone can top up the pool that bonuses and onboarding rewards are paid from
+pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        to: ctx.accounts.reward_pool.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+pub struct ClaimFirstSessionReward<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = FirstSessionReceipt::SPACE,
+        seeds = [b"first_session_reward", user.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, FirstSessionReceipt>,
+
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's used only as a signer for token transfers
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key(),
+        constraint = user_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn claim_first_session_reward(ctx: Context<ClaimFirstSessionReward>) -> Result<()> {
+    let reward = ctx.accounts.focus_program.first_session_reward;
+    require!(reward > 0, FocusError::FirstSessionRewardDisabled);
+    require!(ctx.accounts.user_profile.total_sessions_completed > 0, FocusError::NoSessionCompleted);
+    require!(ctx.accounts.reward_pool.amount >= reward, FocusError::InsufficientBalance);
+
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_pool.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, reward)?;
+
+    // the receipt's init is what stops a second claim
+    let receipt = &mut ctx.accounts.receipt;
+    receipt.user = ctx.accounts.user.key();
+    receipt.bump = ctx.bumps.receipt;
+    receipt.amount = reward;
+    receipt.claimed_at = Clock::get()?.unix_timestamp;
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.total_rewards_earned = user_profile.total_rewards_earned.checked_add(reward).unwrap();
+    user_profile.refresh_reputation();
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DrainRewardPoolDust<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority,
+        constraint = focus_program.treasury != Pubkey::default() @ FocusError::TreasuryNotSet
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = focus_program.treasury
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+
+    /// CHECK: this is a PDA that acts as the vault authority and doesn't need type checking
+    /// as it's used only as a signer for token transfers
+    #[account(
+        seeds = [b"vault_authority"],
+        bump
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// moves less than one whole token back to the treasury, never dipping into the
+// bonuses reserved for active commitments
+pub fn drain_reward_pool_dust(ctx: Context<DrainRewardPoolDust>, amount: u64) -> Result<()> {
+    let one_token = 10u64.checked_pow(ctx.accounts.token_mint.decimals as u32).unwrap();
+    require!(amount < one_token, FocusError::DustAmountTooLarge);
+
+    let remaining = ctx.accounts.reward_pool.amount
+        .checked_sub(amount)
+        .ok_or(FocusError::InsufficientBalance)?;
+    require!(
+        remaining >= ctx.accounts.focus_program.outstanding_bonus_liability,
+        FocusError::BonusLiabilityUnderfunded
+    );
+
+    let seeds = &[
+        b"vault_authority".as_ref(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.reward_pool.to_account_info(),
+        to: ctx.accounts.treasury.to_account_info(),
+        authority: ctx.accounts.vault_authority.to_account_info(),
+    };
+
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+    token::transfer(cpi_ctx, amount)
+}
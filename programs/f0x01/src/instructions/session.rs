@@ -1,20 +1,26 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::error::*;
+use crate::constants::*;
+use crate::utils::check_session_slots;
 
 #[derive(Accounts)]
 #[instruction(session_id: u64)]
 pub struct StartSession<'info> {
     #[account(
         mut,
-        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
         bump = commitment.bump,
-        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+        constraint = commitment.can_run_sessions(&user.key()) @ FocusError::InvalidAuthority
     )]
     pub commitment: Account<'info, FocusCommitment>,
     
+    // init_if_needed so a retried start reaches the handler and fails with SessionAlreadyStarted
+    // rather than the system program's account-in-use error
     #[account(
-        init,
+        init_if_needed,
         payer = user,
         space = SessionRecord::SPACE,
         seeds = [b"session", commitment.key().as_ref(), &session_id.to_le_bytes()],
@@ -24,51 +30,79 @@ pub struct StartSession<'info> {
     
     #[account(
         mut,
-        seeds = [b"user_profile", user.key().as_ref()],
+        seeds = [b"user_profile", commitment.user.as_ref()],
         bump = user_profile.bump
     )]
     pub user_profile: Account<'info, UserProfile>,
     
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn start_session(ctx: Context<StartSession>, session_id: u64) -> Result<()> {
+    require!(!ctx.accounts.focus_program.is_paused, FocusError::ProgramPaused);
     let commitment = &mut ctx.accounts.commitment;
     require!(commitment.is_active, FocusError::CommitmentInactive);
+    require!(!commitment.is_paused, FocusError::CommitmentPaused);
+    
+    // a record that already points at the commitment was written by an earlier start
+    require!(
+        ctx.accounts.session_record.commitment == Pubkey::default(),
+        FocusError::SessionAlreadyStarted
+    );
+    
+    // ids are sequential per commitment so the PDA seed and stored session_number can't diverge
+    require!(session_id == commitment.next_session_id, FocusError::InvalidSessionId);
     
     // calculate current day based on start time
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let day_in_seconds = 86400;
-    let days_elapsed = ((current_timestamp - commitment.start_timestamp) / day_in_seconds) as u8;
+    let days_elapsed = commitment.days_elapsed(current_timestamp);
     
-    require!(days_elapsed < commitment.total_days, FocusError::CommitmentEnded);
+    require!(days_elapsed >= 0, FocusError::CommitmentNotStarted);
+    require!(days_elapsed < commitment.total_days as i64, FocusError::CommitmentEnded);
+    require!(
+        !(ctx.accounts.focus_program.block_extra_sessions && commitment.sessions_exhausted()),
+        FocusError::CommitmentSessionsExhausted
+    );
+    require!(commitment.is_within_focus_window(current_timestamp), FocusError::OutsideFocusWindow);
     
     // check if we're in a new day
-    if days_elapsed > commitment.days_completed {
-        commitment.days_completed = days_elapsed;
-        commitment.sessions_completed_today = 0;
-    }
+    commitment.roll_over_day(current_timestamp);
+    
+    // no session can save a commitment that can't reach the auto-fail threshold any more.
+    // the rejection can't persist auto_failed, refresh_daily_counter records it
+    require!(
+        !commitment.auto_failed && !commitment.should_auto_fail(&ctx.accounts.focus_program, current_timestamp),
+        FocusError::CommitmentAutoFailed
+    );
     
     // check if user already completed all sessions for today
     require!(
         commitment.sessions_completed_today < commitment.sessions_per_day,
         FocusError::DailySessionsCompleted
     );
+    // with ordered slots, today's next session must start in its own slot
+    require!(commitment.is_within_next_slot(current_timestamp), FocusError::OutsideSessionSlot);
     
     // check if enough time has passed since last session
     if commitment.last_session_timestamp > 0 {
-        let min_time_between_sessions = 30 * 60; // 30 minutes in seconds
         require!(
-            current_timestamp - commitment.last_session_timestamp >= min_time_between_sessions,
+            current_timestamp - commitment.last_session_timestamp
+                >= commitment.min_session_gap_secs(&ctx.accounts.focus_program, current_timestamp),
             FocusError::SessionTooSoon
         );
     }
     
     // create new session record
     let session_record = &mut ctx.accounts.session_record;
-    session_record.user = ctx.accounts.user.key();
+    session_record.user = commitment.user;
     session_record.commitment = commitment.key();
     session_record.bump = ctx.bumps.session_record;
     session_record.session_number = session_id;
@@ -76,6 +110,74 @@ pub fn start_session(ctx: Context<StartSession>, session_id: u64) -> Result<()>
     session_record.completed = false;
     session_record.verification_slot = Clock::get()?.slot;
     session_record.end_timestamp = 0;
+    session_record.events_root = [0; 32];
+    session_record.verified_ticks = 0;
+    session_record.platform = 0;
+    session_record.early_bird = false;
+    session_record.rent_payer = ctx.accounts.user.key();
+    commitment.next_session_id += 1;
+    
+    // with streak_on_start, starting a session is what extends the streak. milestone
+    // auto-stake only runs from complete_session, so it doesn't fire in this mode
+    if ctx.accounts.focus_program.streak_on_start {
+        ctx.accounts.user_profile.record_active_day(current_timestamp);
+    }
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VerifySessionEvents<'info> {
+    #[account(
+        mut,
+        seeds = [b"session", commitment.key().as_ref(), &session_record.session_number.to_le_bytes()],
+        bump = session_record.bump,
+        constraint = session_record.user == commitment.user @ FocusError::InvalidAuthority
+    )]
+    pub session_record: Account<'info, SessionRecord>,
+    
+    #[account(
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump = commitment.bump,
+        constraint = commitment.can_run_sessions(&user.key()) @ FocusError::InvalidAuthority
+    )]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    pub user: Signer<'info>,
+}
+
+// proves the app's event tree for this session holds at least `tick_count` ticks by
+// checking the last one, leaf `tick_count - 1`, against `events_root`. leaves are
+// hash(FOCUS_TICK_SEED, session_record, index as u32 le) and parents hash(left, right)
+pub fn verify_session_events(
+    ctx: Context<VerifySessionEvents>,
+    events_root: [u8; 32],
+    tick_count: u32,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let session_record = &mut ctx.accounts.session_record;
+    require!(!session_record.completed, FocusError::SessionAlreadyCompleted);
+    require!(tick_count > 0, FocusError::InvalidEventProof);
+    require!(proof.len() <= MAX_EVENT_PROOF_DEPTH as usize, FocusError::InvalidEventProof);
+    
+    let mut index = tick_count - 1;
+    // a proof this short can't reach the claimed leaf
+    require!((index as u64) < (1u64 << proof.len()), FocusError::InvalidEventProof);
+    
+    let session_key = session_record.key();
+    let mut node = hashv(&[FOCUS_TICK_SEED, session_key.as_ref(), &index.to_le_bytes()]).to_bytes();
+    for sibling in proof.iter() {
+        node = if index & 1 == 0 {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        };
+        index /= 2;
+    }
+    require!(node == events_root, FocusError::InvalidEventProof);
+    
+    session_record.events_root = events_root;
+    session_record.verified_ticks = tick_count;
     
     Ok(())
 }
@@ -86,81 +188,326 @@ pub struct CompleteSession<'info> {
         mut,
         seeds = [b"session", commitment.key().as_ref(), &session_record.session_number.to_le_bytes()],
         bump = session_record.bump,
-        constraint = session_record.user == user.key() @ FocusError::InvalidAuthority
+        constraint = session_record.user == commitment.user @ FocusError::InvalidAuthority
     )]
     pub session_record: Account<'info, SessionRecord>,
     
     #[account(
         mut,
-        seeds = [b"commitment", user.key().as_ref(), &commitment.commitment_id.to_le_bytes()],
+        seeds = [b"commitment", commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
         bump = commitment.bump,
-        constraint = commitment.user == user.key() @ FocusError::InvalidAuthority
+        constraint = commitment.can_run_sessions(&user.key()) @ FocusError::InvalidAuthority
     )]
     pub commitment: Account<'info, FocusCommitment>,
     
     #[account(
         mut,
-        seeds = [b"user_profile", user.key().as_ref()],
+        seeds = [b"user_profile", commitment.user.as_ref()],
         bump = user_profile.bump
     )]
     pub user_profile: Account<'info, UserProfile>,
     
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
+    
+    /// CHECK: only receives the record's rent when the record is closed. a delegate may
+    /// complete a session the owner started, so this isn't necessarily the signer
+    #[account(mut, address = session_record.rent_payer @ FocusError::InvalidRentPayer)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    // the remaining accounts are only needed when the commitment auto-stakes on a streak milestone
+    #[account(
+        mut,
+        constraint = user_token_account.owner == commitment.user,
+        constraint = user_token_account.mint == focus_program.focus_token_mint
+    )]
+    pub user_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [commitment.vault_seed(), commitment.user.as_ref(), &commitment.commitment_id.to_le_bytes()],
+        bump,
+    )]
+    pub vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    
+    // required when the session is tagged with a platform
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Option<Account<'info, GlobalStats>>,
+}
+
+// leading zero bits of hash(POW_SEED, session_record, nonce as u64 le)
+fn pow_leading_zeros(session_record: &Pubkey, nonce: u64) -> u32 {
+    let hash = hashv(&[POW_SEED, session_record.as_ref(), &nonce.to_le_bytes()]).to_bytes();
+    let mut zeros = 0;
+    for byte in hash.iter() {
+        zeros += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    zeros
 }
 
-pub fn complete_session(ctx: Context<CompleteSession>) -> Result<()> {
+// `pow_nonce` is ignored while the program's pow_difficulty is 0
+pub fn complete_session(ctx: Context<CompleteSession>, pow_nonce: u64, platform: Option<u8>) -> Result<()> {
     let session_record = &mut ctx.accounts.session_record;
     let commitment = &mut ctx.accounts.commitment;
     let user_profile = &mut ctx.accounts.user_profile;
+    require!(!ctx.accounts.focus_program.is_paused, FocusError::ProgramPaused);
     
     // verify session wasn't already completed
     require!(!session_record.completed, FocusError::SessionAlreadyCompleted);
     
-    // verify that enough time has passed (25 min focus + 5 min break + 25 min focus = 55 min)
+    // the nonce is bound to this session record, so one solution can't be reused
+    let pow_difficulty = ctx.accounts.focus_program.pow_difficulty;
+    require!(
+        pow_difficulty == 0 || pow_leading_zeros(&session_record.key(), pow_nonce) >= pow_difficulty as u32,
+        FocusError::InvalidProofOfWork
+    );
+    
+    // verify that enough time has passed (25 min focus + 5 min break + 25 min focus = 55 min).
+    // with partial credit on, a session past min_credit_secs counts for its share of that
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let session_duration = 55 * 60; // 55 minutes in seconds
+    let elapsed_secs = current_timestamp - session_record.start_timestamp;
+    let duration_tolerance_secs = ctx.accounts.focus_program.duration_tolerance_secs;
+    let min_credit_secs = ctx.accounts.focus_program.min_credit_secs;
+    let is_full_session = elapsed_secs >= SESSION_DURATION_SECS - duration_tolerance_secs;
     require!(
-        current_timestamp - session_record.start_timestamp >= session_duration,
+        is_full_session || (min_credit_secs > 0 && elapsed_secs >= min_credit_secs),
         FocusError::SessionNotComplete
     );
     
+    // sessions started together and completed later would all pass the duration check. one
+    // person can only focus on one session at a time, so completed sessions have to start at
+    // least a session duration apart, in the order they started
+    require!(
+        commitment.last_completed_start == 0
+            || session_record.start_timestamp
+                >= commitment.last_completed_start + SESSION_DURATION_SECS - duration_tolerance_secs,
+        FocusError::OverlappingSession
+    );
+    
+    // a record without a verification slot is verified by its timestamps alone
+    if session_record.verification_slot == 0 {
+        msg!(
+            "warning: session {} has no verification slot, verifying by time only",
+            session_record.session_number
+        );
+    }
+    
     // use solana's slot timing for additional verification
-    let current_slot = Clock::get()?.slot;
-    let slot_difference = current_slot - session_record.verification_slot;
-    let expected_slots = (session_duration as u64) / 400; // approx slots in 55 minutes
+    let ms_per_slot = ctx.accounts.focus_program.ms_per_slot;
+    let required_slots = if is_full_session {
+        SessionRecord::required_slots(duration_tolerance_secs, ms_per_slot)
+    } else {
+        SessionRecord::required_slots_for(min_credit_secs, ms_per_slot)
+    };
+    check_session_slots(
+        session_record.verification_slot,
+        commitment.creation_slot,
+        Clock::get()?.slot,
+        required_slots,
+        elapsed_secs,
+        ms_per_slot,
+        ctx.accounts.focus_program.max_clock_drift_bps,
+    )?;
+    
+    let required_focus_ticks = ctx.accounts.focus_program.required_focus_ticks;
     require!(
-        slot_difference >= expected_slots.saturating_sub(10), // allow small tolerance
-        FocusError::SlotVerificationFailed
+        session_record.verified_ticks >= required_focus_ticks,
+        FocusError::FocusTicksUnverified
     );
     
+    // tagged sessions feed the per-platform counters on GlobalStats
+    if let Some(platform) = platform {
+        require!(
+            (PLATFORM_IOS..=PLATFORM_DESKTOP).contains(&platform),
+            FocusError::InvalidPlatform
+        );
+        ctx.accounts.global_stats
+            .as_mut()
+            .ok_or(FocusError::GlobalStatsRequired)?
+            .record_platform_session(platform);
+        session_record.platform = platform;
+    }
+    
     // mark session as completed
     session_record.completed = true;
     session_record.end_timestamp = current_timestamp;
     commitment.last_session_timestamp = current_timestamp;
-    commitment.sessions_completed_today += 1;
-    
-    // update user profile stats
-    user_profile.total_sessions_completed += 1;
-    
-    // update streak logic
-    let day_in_seconds = 86400;
-    let today_timestamp = (current_timestamp / day_in_seconds) * day_in_seconds;
-    let last_active_day_timestamp = (user_profile.last_active_day / day_in_seconds) * day_in_seconds;
-    
-    if today_timestamp > last_active_day_timestamp {
-        // check if this is consecutive day (yesterday)
-        if today_timestamp - last_active_day_timestamp <= day_in_seconds {
-            user_profile.current_streak += 1;
-            if user_profile.current_streak > user_profile.best_streak {
-                user_profile.best_streak = user_profile.current_streak;
-            }
-        } else {
-            // streak broken
-            user_profile.current_streak = 1;
+    commitment.last_completed_start = session_record.start_timestamp;
+    // a session belongs to the day it started on, even when it finishes past the day boundary
+    commitment.credit_session(session_record.start_timestamp, elapsed_secs, is_full_session);
+    if commitment.is_early_bird(current_timestamp, ctx.accounts.focus_program.early_bird_hour) {
+        session_record.early_bird = true;
+        commitment.early_sessions = commitment.early_sessions.saturating_add(1);
+    }
+    let session_secs = elapsed_secs as u64;
+    
+    // update user profile stats. partial credit only counts toward the commitment's
+    // success rate; the lifetime and league counts are of full sessions
+    if is_full_session {
+        user_profile.total_sessions_completed += 1;
+        user_profile.record_league_session(session_record.start_timestamp)?;
+    }
+    user_profile.longest_session_secs = user_profile.longest_session_secs.max(session_secs);
+    user_profile.record_focus_secs(session_record.start_timestamp, session_secs);
+    
+    // update streak logic, unless start_session already did, for the day the session started
+    let hit_milestone = !ctx.accounts.focus_program.streak_on_start
+        && user_profile.record_active_day(session_record.start_timestamp);
+    user_profile.refresh_reputation();
+    
+    // compound a share of the rewards not yet restaked into this commitment's stake. only
+    // the owner can authorize the transfer, so a delegate's completion skips it, and a
+    // locked commitment's stake stays as it was locked
+    let signed_by_owner = ctx.accounts.user.key() == commitment.user;
+    if hit_milestone
+        && commitment.auto_stake_on_milestone
+        && commitment.is_active
+        && !commitment.is_locked
+        && signed_by_owner
+    {
+        let unstaked_rewards = user_profile.total_rewards_earned.saturating_sub(user_profile.rewards_auto_staked);
+        let amount = unstaked_rewards.checked_mul(AUTO_STAKE_BPS).unwrap() / BPS_DENOMINATOR;
+        // skipped rather than failing the session when the TVL cap is reached
+        if let (true, Some(user_token_account), Some(vault), Some(token_program)) = (
+            amount > 0 && ctx.accounts.focus_program.can_stake(amount),
+            ctx.accounts.user_token_account.as_ref(),
+            ctx.accounts.vault.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        ) {
+            let cpi_accounts = Transfer {
+                from: user_token_account.to_account_info(),
+                to: vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+            token::transfer(cpi_ctx, amount)?;
+            
+            let added_bonus = amount.checked_mul(commitment.locked_reward_rate_bps).unwrap() / BPS_DENOMINATOR;
+            commitment.amount_staked = commitment.amount_staked.checked_add(amount).unwrap();
+            commitment.reserved_bonus = commitment.reserved_bonus.checked_add(added_bonus).unwrap();
+            user_profile.rewards_auto_staked = user_profile.rewards_auto_staked.checked_add(amount).unwrap();
+            let program = &mut ctx.accounts.focus_program;
+            program.total_staked = program.total_staked.checked_add(amount).unwrap();
+            program.outstanding_bonus_liability = program.outstanding_bonus_liability.checked_add(added_bonus).unwrap();
         }
-        user_profile.last_active_day = today_timestamp;
+    }
+    
+    // the commitment now holds the session's summary, so the record can go
+    if !commitment.keep_session_records {
+        commitment.record_closed_session(elapsed_secs, is_full_session);
+        session_record.close(ctx.accounts.rent_payer.to_account_info())?;
     }
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct RefreshDailyCounter<'info> {
+    #[account(mut)]
+    pub commitment: Account<'info, FocusCommitment>,
+    
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+}
+
+// permissionless: rolls the cached daily counter onto today and reconciles it with the
+// completed SessionRecords started today, passed as remaining_accounts. the counter is only ever raised
+// by the recount, so omitting records can't be used to bypass the daily cap
+pub fn refresh_daily_counter<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefreshDailyCounter<'info>>,
+) -> Result<()> {
+    let commitment = &mut ctx.accounts.commitment;
+    require!(commitment.is_active, FocusError::CommitmentInactive);
+
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    commitment.roll_over_day(current_timestamp);
+    let today = commitment.days_elapsed(current_timestamp);
+
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut completed_today: u8 = 0;
+    for account_info in ctx.remaining_accounts.iter() {
+        require!(!seen.contains(account_info.key), FocusError::DuplicateAccount);
+        seen.push(account_info.key());
+
+        let session_record = Account::<SessionRecord>::try_from(account_info)?;
+        require!(session_record.commitment == commitment.key(), FocusError::SessionCommitmentMismatch);
+
+        if session_record.completed && commitment.days_elapsed(session_record.start_timestamp) == today {
+            completed_today = completed_today.saturating_add(1);
+        }
+    }
+
+    commitment.sessions_completed_today = commitment
+        .sessions_completed_today
+        .max(completed_today)
+        .min(commitment.sessions_per_day);
+    
+    // marks a commitment that fell too far behind, freeing its stake for an early claim
+    if commitment.should_auto_fail(&ctx.accounts.focus_program, current_timestamp) {
+        commitment.auto_failed = true;
+    }
+
+    Ok(())
+}
+
+// session credit recounted from SessionRecords rather than the commitment's cached counters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SessionRecount {
+    // full sessions, at most the commitment's expected sessions like sessions_completed
+    pub sessions_completed: u16,
+    pub partial_credit_bps: u64,
+}
+
+// recounts the completed SessionRecords of the commitment's current term among `records`,
+// on top of the sessions whose records complete_session closed
+pub fn recount_sessions<'info>(
+    commitment: &Account<'info, FocusCommitment>,
+    program: &FocusProgram,
+    records: &'info [AccountInfo<'info>],
+) -> Result<SessionRecount> {
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(records.len());
+    let mut sessions_completed: u16 = commitment.closed_full_sessions;
+    let mut partial_credit_bps: u64 = commitment.closed_partial_credit_bps;
+    for account_info in records.iter() {
+        require!(!seen.contains(account_info.key), FocusError::DuplicateAccount);
+        seen.push(account_info.key());
+
+        let session_record = Account::<SessionRecord>::try_from(account_info)?;
+        require!(session_record.commitment == commitment.key(), FocusError::SessionCommitmentMismatch);
+        // records from before a restart belong to an earlier term
+        if !session_record.completed || session_record.start_timestamp < commitment.start_timestamp {
+            continue;
+        }
+
+        let elapsed_secs = session_record.end_timestamp - session_record.start_timestamp;
+        if elapsed_secs >= SESSION_DURATION_SECS - program.duration_tolerance_secs {
+            sessions_completed = sessions_completed.saturating_add(1);
+        } else {
+            partial_credit_bps = partial_credit_bps.saturating_add(FocusCommitment::partial_credit(elapsed_secs));
+        }
+    }
+
+    Ok(SessionRecount {
+        sessions_completed: sessions_completed.min(commitment.expected_sessions()),
+        partial_credit_bps,
+    })
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+use crate::utils::tuned_reward_rate;
+//initialize_global_stats, rollover_period_stats, auto_tune_reward_rate
+
+#[derive(Accounts)]
+pub struct InitializeGlobalStats<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GlobalStats::SPACE,
+        seeds = [b"global_stats"],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.bump = ctx.bumps.global_stats;
+    global_stats.total_rewards_paid = 0;
+    global_stats.total_bonus_paid = 0;
+    global_stats.period = 0;
+    global_stats.period_started_at = Clock::get()?.unix_timestamp;
+    global_stats.period_rewards_paid = 0;
+    global_stats.period_bonus_paid = 0;
+    global_stats.platform_sessions = [0; PLATFORM_COUNT];
+    global_stats.terms_claimed = 0;
+    global_stats.total_success_bps = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RolloverPeriodStats<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump,
+        has_one = authority @ FocusError::InvalidAuthority
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        mut,
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    // keyed by the period being archived, so the same period can never be archived twice
+    #[account(
+        init,
+        payer = authority,
+        space = PeriodStats::SPACE,
+        seeds = [b"period_stats".as_ref(), &global_stats.period.to_le_bytes()],
+        bump
+    )]
+    pub period_stats: Account<'info, PeriodStats>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// archives the live period's payout totals and starts a new period. periods last at least
+// MIN_STATS_PERIOD_SECS so the history can't be flooded with empty ones
+pub fn rollover_period_stats(ctx: Context<RolloverPeriodStats>) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let global_stats = &mut ctx.accounts.global_stats;
+    require!(
+        current_timestamp - global_stats.period_started_at >= MIN_STATS_PERIOD_SECS,
+        FocusError::StatsPeriodNotEnded
+    );
+
+    let period_stats = &mut ctx.accounts.period_stats;
+    period_stats.bump = ctx.bumps.period_stats;
+    period_stats.period = global_stats.period;
+    period_stats.started_at = global_stats.period_started_at;
+    period_stats.ended_at = current_timestamp;
+    period_stats.rewards_paid = global_stats.period_rewards_paid;
+    period_stats.bonus_paid = global_stats.period_bonus_paid;
+
+    global_stats.period += 1;
+    global_stats.period_started_at = current_timestamp;
+    global_stats.period_rewards_paid = 0;
+    global_stats.period_bonus_paid = 0;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AutoTuneRewardRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        seeds = [b"global_stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    pub keeper: Signer<'info>,
+}
+
+// permissionless: moves reward_rate_bps one step towards what the average completion of
+// claimed terms calls for. a step at most every RATE_TUNE_INTERVAL_SECS, so a keeper can't
+// walk the rate to a bound in one go. returns the new rate
+pub fn auto_tune_reward_rate(ctx: Context<AutoTuneRewardRate>) -> Result<u64> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.focus_program;
+    require!(program.tune_max_step_bps > 0, FocusError::RateTuningDisabled);
+    require!(
+        current_timestamp - program.last_rate_tune_at >= RATE_TUNE_INTERVAL_SECS,
+        FocusError::RateTuneNotDue
+    );
+    let average_success_bps = ctx.accounts.global_stats
+        .average_success_bps()
+        .ok_or(FocusError::NoCompletionData)?;
+
+    program.reward_rate_bps = tuned_reward_rate(
+        program.reward_rate_bps,
+        average_success_bps,
+        program.tune_min_rate_bps,
+        program.tune_max_rate_bps,
+        program.tune_max_step_bps,
+    );
+    program.last_rate_tune_at = current_timestamp;
+    msg!("average completion: {} bps, reward rate: {} bps", average_success_bps, program.reward_rate_bps);
+
+    Ok(program.reward_rate_bps)
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
-// use crate::error::*;
+use crate::error::*;
+use crate::constants::*;
 
 #[derive(Accounts)]
 pub struct CreateUserProfile<'info> {
@@ -12,7 +13,11 @@ pub struct CreateUserProfile<'info> {
         bump
     )]
     pub user_profile: Account<'info, UserProfile>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
     pub focus_program: Account<'info, FocusProgram>,
     #[account(mut)]
     pub user: Signer<'info>,
@@ -28,9 +33,93 @@ pub fn create_user_profile(ctx: Context<CreateUserProfile>) -> Result<()> {
     user_profile.current_streak = 0;
     user_profile.best_streak = 0;
     user_profile.last_active_day = Clock::get()?.unix_timestamp;
+    user_profile.reminder_hour = 0;
+    user_profile.reminders_enabled = false;
+    user_profile.rewards_auto_staked = 0;
+    user_profile.referrer = Pubkey::default();
+    user_profile.qualified_referrals = 0;
+    user_profile.claimed_airdrop_tiers = 0;
+    user_profile.weekly_sessions = 0;
+    user_profile.league_week = WeeklyLeagueEntry::week_of(user_profile.last_active_day);
+    user_profile.longest_session_secs = 0;
+    user_profile.reputation = 0;
+    user_profile.total_expected_sessions_all_time = 0;
+    user_profile.last_commitment_end = 0;
+    user_profile.last_claim_at = 0;
+    user_profile.daily_minutes_goal = 0;
+    user_profile.focus_secs_today = 0;
+    user_profile.focus_day = 0;
+    user_profile.referral_qualified = false;
     
     let program = &mut ctx.accounts.focus_program;
     program.total_users = program.total_users.checked_add(1).unwrap();
     
     Ok(())
-}
\ No newline at end of file
+}
+
+#[derive(Accounts)]
+pub struct UpdateUserProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub user: Signer<'info>,
+}
+
+pub fn set_reminder_prefs(
+    ctx: Context<UpdateUserProfile>,
+    reminder_hour: u8,
+    reminders_enabled: bool,
+) -> Result<()> {
+    require!(reminder_hour < 24, FocusError::InvalidReminderHour);
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.reminder_hour = reminder_hour;
+    user_profile.reminders_enabled = reminders_enabled;
+
+    Ok(())
+}
+
+// a daily target in focus minutes, met by any mix of sessions. 0 clears it
+pub fn set_daily_minutes_goal(ctx: Context<UpdateUserProfile>, daily_minutes_goal: u16) -> Result<()> {
+    require!(daily_minutes_goal as i64 * 60 <= SECONDS_PER_DAY, FocusError::InvalidMinutesGoal);
+
+    ctx.accounts.user_profile.daily_minutes_goal = daily_minutes_goal;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(referrer: Pubkey)]
+pub struct SetReferrer<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_profile", user.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    // claims paying the referral bonus need this profile, so it has to exist up front
+    #[account(
+        seeds = [b"user_profile", referrer.as_ref()],
+        bump = referrer_profile.bump
+    )]
+    pub referrer_profile: Account<'info, UserProfile>,
+
+    pub user: Signer<'info>,
+}
+
+// recorded once, before the user has completed any session
+pub fn set_referrer(ctx: Context<SetReferrer>, referrer: Pubkey) -> Result<()> {
+    let user_profile = &mut ctx.accounts.user_profile;
+    require!(referrer != user_profile.user && referrer != Pubkey::default(), FocusError::InvalidReferrer);
+    require!(user_profile.referrer == Pubkey::default(), FocusError::ReferrerAlreadySet);
+    require!(user_profile.total_sessions_completed == 0, FocusError::ReferrerAlreadySet);
+
+    user_profile.referrer = referrer;
+
+    Ok(())
+}
@@ -0,0 +1,422 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+use crate::state::*;
+use crate::error::*;
+use crate::constants::*;
+use crate::instructions::commitment::term_payout;
+use crate::instructions::session::{recount_sessions, SessionRecount};
+//read-only handlers for frontends, called via simulation (`.view()`)
+
+#[derive(Accounts)]
+pub struct CommitmentView<'info> {
+    pub commitment: Account<'info, FocusCommitment>,
+}
+
+pub fn next_session_eligible_at(ctx: Context<CommitmentRateView>) -> Result<i64> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    Ok(ctx.accounts.commitment.next_session_eligible_at(&ctx.accounts.focus_program, current_timestamp))
+}
+
+// the rate, in bps, the claim actually used; unaffected by later rate changes
+pub fn claimed_reward_rate(ctx: Context<CommitmentView>) -> Result<u64> {
+    let commitment = &ctx.accounts.commitment;
+    require!(!commitment.is_active, FocusError::CommitmentNotClaimed);
+    Ok(commitment.effective_reward_rate_used)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ExpiryStatus {
+    pub expiring_soon: bool,
+    // 0 once the deadline has passed
+    pub secs_remaining: i64,
+}
+
+// lets a keeper batch reminders or finalisation for active commitments whose deadline
+// falls within `within_secs`, including ones already past it
+pub fn is_expiring_soon(ctx: Context<CommitmentView>, within_secs: i64) -> Result<ExpiryStatus> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let commitment = &ctx.accounts.commitment;
+    let secs_remaining = (commitment.deadline() - current_timestamp).max(0);
+
+    Ok(ExpiryStatus {
+        expiring_soon: commitment.is_active && secs_remaining <= within_secs,
+        secs_remaining,
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TierProgress {
+    // to get the full stake back, the commitment's fail_threshold_bps (75% by default)
+    pub sessions_to_stake_back: u16,
+    // to reach the 90% bonus tier
+    pub sessions_to_bonus: u16,
+}
+
+// powers "2 more sessions for full rewards" nudges; both are 0 at the top tier
+pub fn sessions_to_next_tier(ctx: Context<CommitmentView>) -> Result<TierProgress> {
+    let commitment = &ctx.accounts.commitment;
+
+    Ok(TierProgress {
+        sessions_to_stake_back: commitment.sessions_to_tier(commitment.fail_threshold_bps),
+        sessions_to_bonus: commitment.sessions_to_tier(BONUS_THRESHOLD_BPS),
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum CompletionStatus {
+    Ready,
+    AlreadyCompleted,
+    // the 55 minutes, less the program's duration tolerance, haven't passed
+    TooEarly,
+    // enough wall-clock time has passed but not enough slots
+    SlotsPending,
+}
+
+// aggregates a reader would otherwise have to recompute from the raw counters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitmentStats {
+    pub expected_sessions: u16,
+    pub success_bps: u64,
+    pub active_days: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitmentExport {
+    pub version: u8,
+    pub commitment: Pubkey,
+    pub state: FocusCommitment,
+    pub stats: CommitmentStats,
+}
+
+// the commitment as one borsh blob for off-chain archival: COMMITMENT_EXPORT_VERSION, the
+// commitment's address, its account data without the discriminator, then CommitmentStats
+pub fn export_commitment(ctx: Context<CommitmentView>) -> Result<Vec<u8>> {
+    let commitment = &ctx.accounts.commitment;
+    let export = CommitmentExport {
+        version: COMMITMENT_EXPORT_VERSION,
+        commitment: commitment.key(),
+        state: (**commitment).clone(),
+        stats: CommitmentStats {
+            expected_sessions: commitment.expected_sessions(),
+            success_bps: commitment.success_bps(),
+            active_days: commitment.active_days(),
+        },
+    };
+
+    Ok(export.try_to_vec()?)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompletionCheck {
+    pub status: CompletionStatus,
+    pub secs_remaining: i64,
+    pub slots_remaining: u64,
+}
+
+#[derive(Accounts)]
+pub struct SessionCompletionView<'info> {
+    pub session_record: Account<'info, SessionRecord>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+}
+
+// runs complete_session's already-completed, duration and slot checks without
+// submitting it, so frontends don't pay for a transaction that would fail
+pub fn can_complete_session(ctx: Context<SessionCompletionView>) -> Result<CompletionCheck> {
+    let clock = Clock::get()?;
+    let session_record = &ctx.accounts.session_record;
+
+    let required_secs = SESSION_DURATION_SECS - ctx.accounts.focus_program.duration_tolerance_secs;
+    let secs_remaining = (session_record.start_timestamp + required_secs - clock.unix_timestamp).max(0);
+    // complete_session checks a record without a verification slot by time only
+    let slots_remaining = if session_record.verification_slot == 0 {
+        0
+    } else {
+        let slot_difference = clock.slot.saturating_sub(session_record.verification_slot);
+        let program = &ctx.accounts.focus_program;
+        SessionRecord::required_slots(program.duration_tolerance_secs, program.ms_per_slot).saturating_sub(slot_difference)
+    };
+
+    let status = if session_record.completed {
+        CompletionStatus::AlreadyCompleted
+    } else if secs_remaining > 0 {
+        CompletionStatus::TooEarly
+    } else if slots_remaining > 0 {
+        CompletionStatus::SlotsPending
+    } else {
+        CompletionStatus::Ready
+    };
+
+    Ok(CompletionCheck {
+        status,
+        secs_remaining,
+        slots_remaining,
+    })
+}
+
+#[derive(Accounts)]
+pub struct CommitmentRateView<'info> {
+    pub commitment: Account<'info, FocusCommitment>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+}
+
+// the bonus rate, in bps, a claim made right now would use
+pub fn effective_reward_rate(ctx: Context<CommitmentRateView>) -> Result<u64> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    Ok(ctx.accounts.commitment.reward_rate_bps(&ctx.accounts.focus_program, current_timestamp))
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SessionCountCheck {
+    pub cached: SessionRecount,
+    pub recounted: SessionRecount,
+}
+
+// what strict_claim_verification would credit with the SessionRecords passed as
+// remaining_accounts, next to the commitment's cached counters
+pub fn verify_session_count<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CommitmentRateView<'info>>,
+) -> Result<SessionCountCheck> {
+    let commitment = &ctx.accounts.commitment;
+    let recounted = recount_sessions(commitment, &ctx.accounts.focus_program, ctx.remaining_accounts)?;
+
+    Ok(SessionCountCheck {
+        cached: SessionRecount {
+            sessions_completed: commitment.sessions_completed,
+            partial_credit_bps: commitment.partial_credit_bps,
+        },
+        recounted,
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+pub enum ClaimTier {
+    // 90%+, or anywhere a reward curve pays a bonus: the stake back plus the bonus
+    Bonus,
+    // at or above the commitment's fail_threshold_bps: the stake back
+    StakeBack,
+    // below the failure threshold: part of the stake back
+    PartialRefund,
+    // nothing back: too few active days, or a sponsored commitment that failed
+    Forfeited,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimPreview {
+    pub success_bps: u64,
+    pub tier: ClaimTier,
+    // principal refunded, in bps of the stake
+    pub refund_bps: u64,
+    pub refund_amount: u64,
+    pub bonus_amount: u64,
+    // gifted tokens paid out alongside the refund
+    pub gift_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPreviewView<'info> {
+    pub commitment: Account<'info, FocusCommitment>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(address = focus_program.focus_token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    // only consulted when the program has a habit oracle configured
+    #[account(
+        seeds = [b"habit_attestation", commitment.user.as_ref()],
+        bump = habit_attestation.bump
+    )]
+    pub habit_attestation: Option<Account<'info, HabitAttestation>>,
+}
+
+// what claim_rewards would pay if the term ended with the sessions completed so far. unlike
+// the claim it doesn't wait for the deadline, and it uses the cached session counters even
+// under strict_claim_verification
+pub fn preview_claim(ctx: Context<ClaimPreviewView>) -> Result<ClaimPreview> {
+    let commitment = &ctx.accounts.commitment;
+    let program = &ctx.accounts.focus_program;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    let success_bps = program.oracle_weighted_success_bps(
+        commitment.success_bps(),
+        ctx.accounts.habit_attestation.as_deref(),
+    );
+    let reward_rate_bps = commitment.reward_rate_bps(program, current_timestamp);
+    let (refund_amount, bonus_amount) =
+        term_payout(commitment, program, success_bps, reward_rate_bps, ctx.accounts.token_mint.decimals);
+
+    let tier = if refund_amount == 0 {
+        ClaimTier::Forfeited
+    } else if success_bps >= BONUS_THRESHOLD_BPS || bonus_amount > 0 {
+        ClaimTier::Bonus
+    } else if success_bps >= commitment.fail_threshold_bps {
+        ClaimTier::StakeBack
+    } else {
+        ClaimTier::PartialRefund
+    };
+    let refund_bps = refund_amount
+        .checked_mul(BPS_DENOMINATOR)
+        .unwrap()
+        .checked_div(commitment.amount_staked)
+        .unwrap_or(0);
+
+    Ok(ClaimPreview {
+        success_bps,
+        tier,
+        refund_bps,
+        refund_amount,
+        bonus_amount,
+        gift_amount: commitment.gift_returned(refund_amount),
+    })
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CommitmentSummary {
+    pub commitment: Pubkey,
+    pub remaining_sessions_today: u8,
+    pub next_session_eligible_at: i64,
+    // stake plus bonus at the current effective rate, if the commitment is fully met
+    pub projected_reward: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Dashboard {
+    pub current_streak: u16,
+    pub best_streak: u16,
+    pub total_sessions_completed: u64,
+    pub total_rewards_earned: u64,
+    // total_sessions_completed over every session committed to, in bps
+    pub all_time_completion_bps: u64,
+    // the profile's daily_minutes_goal is set and today's focus time reaches it
+    pub daily_goal_met: bool,
+    pub commitments: Vec<CommitmentSummary>,
+}
+
+#[derive(Accounts)]
+pub struct DashboardView<'info> {
+    pub user_profile: Account<'info, UserProfile>,
+
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+}
+
+// bundles the profile with a summary of each of the user's active commitments passed
+// as remaining_accounts. return data is capped at 1024 bytes, about 20 commitments
+pub fn get_dashboard<'info>(ctx: Context<'_, '_, 'info, 'info, DashboardView<'info>>) -> Result<Dashboard> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let user_profile = &ctx.accounts.user_profile;
+    let program = &ctx.accounts.focus_program;
+
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    let mut commitments = Vec::with_capacity(ctx.remaining_accounts.len());
+    for account_info in ctx.remaining_accounts.iter() {
+        require!(!seen.contains(account_info.key), FocusError::DuplicateAccount);
+        seen.push(account_info.key());
+
+        let commitment = Account::<FocusCommitment>::try_from(account_info)?;
+        require!(commitment.user == user_profile.user, FocusError::InvalidAuthority);
+        if !commitment.is_active {
+            continue;
+        }
+
+        let bonus = commitment.amount_staked
+            .checked_mul(commitment.reward_rate_bps(program, current_timestamp))
+            .unwrap() / BPS_DENOMINATOR;
+        commitments.push(CommitmentSummary {
+            commitment: commitment.key(),
+            remaining_sessions_today: commitment.sessions_per_day
+                .saturating_sub(commitment.sessions_today(current_timestamp)),
+            next_session_eligible_at: commitment.next_session_eligible_at(program, current_timestamp),
+            projected_reward: commitment.amount_staked.checked_add(bonus).unwrap(),
+        });
+    }
+
+    Ok(Dashboard {
+        current_streak: user_profile.current_streak,
+        best_streak: user_profile.best_streak,
+        total_sessions_completed: user_profile.total_sessions_completed,
+        total_rewards_earned: user_profile.total_rewards_earned,
+        all_time_completion_bps: user_profile.all_time_completion_bps(),
+        daily_goal_met: user_profile.daily_goal_met(current_timestamp),
+        commitments,
+    })
+}
+
+#[derive(Accounts)]
+pub struct SolvencyView<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+
+    #[account(
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SolvencyReport {
+    pub pool_balance: u64,
+    pub outstanding_bonus_liability: u64,
+    // pool balance per unit of liability in bps, u64::MAX while nothing is owed
+    pub solvency_ratio_bps: u64,
+    pub is_solvent: bool,
+}
+
+// compares the reward pool against the full-completion bonuses reserved for active
+// commitments, which create_commitment adds to and claims and cancellations release
+pub fn check_solvency(ctx: Context<SolvencyView>) -> Result<SolvencyReport> {
+    let pool_balance = ctx.accounts.reward_pool.amount;
+    let liability = ctx.accounts.focus_program.outstanding_bonus_liability;
+    let solvency_ratio_bps = if liability == 0 {
+        u64::MAX
+    } else {
+        (pool_balance as u128 * BPS_DENOMINATOR as u128 / liability as u128).min(u64::MAX as u128) as u64
+    };
+
+    Ok(SolvencyReport {
+        pool_balance,
+        outstanding_bonus_liability: liability,
+        solvency_ratio_bps,
+        is_solvent: pool_balance >= liability,
+    })
+}
+
+#[derive(Accounts)]
+pub struct ProgramConfigView<'info> {
+    #[account(
+        seeds = [b"focus_program"],
+        bump = focus_program.bump
+    )]
+    pub focus_program: Account<'info, FocusProgram>,
+}
+
+// the continuous bonus set_reward_curve pays on a stake at a given success rate, before the
+// bonus cap and rounding; 0 while rewards are tiered
+pub fn reward_curve_bonus(ctx: Context<ProgramConfigView>, amount_staked: u64, success_bps: u64) -> Result<u64> {
+    let program = &ctx.accounts.focus_program;
+    if !program.has_reward_curve() {
+        return Ok(0);
+    }
+    Ok(program.curve_bonus(amount_staked, success_bps, program.curve_floor_bps))
+}
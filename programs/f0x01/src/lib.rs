@@ -5,8 +5,10 @@ use crate::instructions::*;
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 pub use constants::*;
 pub use state::*;
@@ -21,14 +23,253 @@ pub mod f0x01 {
     //     initialize::handler(ctx)
     // }
 
-    pub fn initialize_program(ctx: Context<InitializeProgram>, reward_rate: u64) -> Result<()> {
-        instructions::initialize_program(ctx, reward_rate)
+    pub fn initialize_program(ctx: Context<InitializeProgram>, reward_rate_bps: u64) -> Result<()> {
+        instructions::initialize_program(ctx, reward_rate_bps)
+    }
+
+    //program configuration
+    pub fn set_reward_rate(ctx: Context<UpdateProgramConfig>, reward_rate_bps: u64) -> Result<()> {
+        instructions::admin::set_reward_rate(ctx, reward_rate_bps)
+    }
+
+    pub fn upgrade_legacy_program(ctx: Context<UpgradeLegacyProgram>) -> Result<()> {
+        instructions::admin::upgrade_legacy_program(ctx)
+    }
+
+    pub fn set_first_session_reward(ctx: Context<UpdateProgramConfig>, first_session_reward: u64) -> Result<()> {
+        instructions::admin::set_first_session_reward(ctx, first_session_reward)
+    }
+
+    pub fn set_max_total_staked(ctx: Context<UpdateProgramConfig>, max_total_staked: u64) -> Result<()> {
+        instructions::admin::set_max_total_staked(ctx, max_total_staked)
+    }
+
+    pub fn set_streak_airdrop_amounts(ctx: Context<UpdateProgramConfig>, streak_airdrop_amounts: [u64; 3]) -> Result<()> {
+        instructions::admin::set_streak_airdrop_amounts(ctx, streak_airdrop_amounts)
+    }
+
+    pub fn set_referral_bonus(ctx: Context<UpdateProgramConfig>, referral_bonus_bps: u64) -> Result<()> {
+        instructions::admin::set_referral_bonus(ctx, referral_bonus_bps)
+    }
+
+    pub fn set_reward_curve(
+        ctx: Context<UpdateProgramConfig>,
+        floor_bps: u64,
+        ceiling_bps: u64,
+        max_bonus_bps: u64,
+    ) -> Result<()> {
+        instructions::admin::set_reward_curve(ctx, floor_bps, ceiling_bps, max_bonus_bps)
+    }
+
+    pub fn set_rate_tuning(
+        ctx: Context<UpdateProgramConfig>,
+        min_rate_bps: u64,
+        max_rate_bps: u64,
+        max_step_bps: u64,
+    ) -> Result<()> {
+        instructions::admin::set_rate_tuning(ctx, min_rate_bps, max_rate_bps, max_step_bps)
+    }
+
+    pub fn set_perfect_streak_bonus(ctx: Context<UpdateProgramConfig>, perfect_streak_bonus_bps: u64) -> Result<()> {
+        instructions::admin::set_perfect_streak_bonus(ctx, perfect_streak_bonus_bps)
+    }
+
+    pub fn set_early_bird(ctx: Context<UpdateProgramConfig>, early_bird_hour: u8, bonus_bps: u64) -> Result<()> {
+        instructions::admin::set_early_bird(ctx, early_bird_hour, bonus_bps)
+    }
+
+    pub fn set_rate_policy(ctx: Context<UpdateProgramConfig>, rate_policy: RatePolicy) -> Result<()> {
+        instructions::admin::set_rate_policy(ctx, rate_policy)
+    }
+
+    pub fn set_claim_cooldown(ctx: Context<UpdateProgramConfig>, claim_cooldown_secs: i64) -> Result<()> {
+        instructions::admin::set_claim_cooldown(ctx, claim_cooldown_secs)
+    }
+
+    pub fn set_max_backdate(ctx: Context<UpdateProgramConfig>, max_backdate_secs: i64) -> Result<()> {
+        instructions::admin::set_max_backdate(ctx, max_backdate_secs)
+    }
+
+    pub fn set_max_clock_drift(ctx: Context<UpdateProgramConfig>, max_clock_drift_bps: u64) -> Result<()> {
+        instructions::admin::set_max_clock_drift(ctx, max_clock_drift_bps)
+    }
+
+    pub fn set_duration_tolerance(ctx: Context<UpdateProgramConfig>, duration_tolerance_secs: i64) -> Result<()> {
+        instructions::admin::set_duration_tolerance(ctx, duration_tolerance_secs)
+    }
+
+    pub fn set_funding_sources(
+        ctx: Context<UpdateProgramConfig>,
+        bonus_source: FundingSource,
+        principal_source: FundingSource,
+        partial_refund_source: FundingSource,
+    ) -> Result<()> {
+        instructions::admin::set_funding_sources(ctx, bonus_source, principal_source, partial_refund_source)
+    }
+
+    pub fn set_auto_fail_threshold(ctx: Context<UpdateProgramConfig>, auto_fail_threshold_bps: u64) -> Result<()> {
+        instructions::admin::set_auto_fail_threshold(ctx, auto_fail_threshold_bps)
+    }
+
+    pub fn set_strict_claim_verification(ctx: Context<UpdateProgramConfig>, strict_claim_verification: bool) -> Result<()> {
+        instructions::admin::set_strict_claim_verification(ctx, strict_claim_verification)
+    }
+
+    pub fn set_streak_grace_days(ctx: Context<UpdateProgramConfig>, streak_grace_days: u8) -> Result<()> {
+        instructions::admin::set_streak_grace_days(ctx, streak_grace_days)
+    }
+
+    pub fn set_min_credit_secs(ctx: Context<UpdateProgramConfig>, min_credit_secs: i64) -> Result<()> {
+        instructions::admin::set_min_credit_secs(ctx, min_credit_secs)
+    }
+
+    pub fn set_catch_up_gap(ctx: Context<UpdateProgramConfig>, catch_up_gap_secs: i64) -> Result<()> {
+        instructions::admin::set_catch_up_gap(ctx, catch_up_gap_secs)
+    }
+
+    pub fn set_max_early_penalty(ctx: Context<UpdateProgramConfig>, max_early_penalty_bps: u64) -> Result<()> {
+        instructions::admin::set_max_early_penalty(ctx, max_early_penalty_bps)
+    }
+
+    pub fn relink_session(ctx: Context<RelinkSession>) -> Result<()> {
+        instructions::admin::relink_session(ctx)
+    }
+
+    pub fn credit_missed_session(
+        ctx: Context<CreditMissedSession>,
+        session_id: u64,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<()> {
+        instructions::admin::credit_missed_session(ctx, session_id, start_timestamp, end_timestamp)
+    }
+
+    pub fn set_bonus_minting(ctx: Context<SetBonusMinting>, mint_bonuses: bool, mint_cap_per_epoch: u64) -> Result<()> {
+        instructions::admin::set_bonus_minting(ctx, mint_bonuses, mint_cap_per_epoch)
+    }
+
+    pub fn set_habit_oracle(ctx: Context<UpdateProgramConfig>, habit_oracle: Pubkey, oracle_weight_bps: u64) -> Result<()> {
+        instructions::admin::set_habit_oracle(ctx, habit_oracle, oracle_weight_bps)
+    }
+
+    pub fn set_min_stake(ctx: Context<UpdateProgramConfig>, min_stake_tokens: u64) -> Result<()> {
+        instructions::admin::set_min_stake(ctx, min_stake_tokens)
+    }
+
+    pub fn set_min_stake_per_day(ctx: Context<UpdateProgramConfig>, min_stake_per_day: u64) -> Result<()> {
+        instructions::admin::set_min_stake_per_day(ctx, min_stake_per_day)
+    }
+
+    pub fn set_ms_per_slot(ctx: Context<UpdateProgramConfig>, ms_per_slot: u64) -> Result<()> {
+        instructions::admin::set_ms_per_slot(ctx, ms_per_slot)
+    }
+
+    pub fn set_checkpoint_interval(ctx: Context<UpdateProgramConfig>, checkpoint_interval_secs: i64) -> Result<()> {
+        instructions::admin::set_checkpoint_interval(ctx, checkpoint_interval_secs)
+    }
+
+    pub fn set_forfeit_destination(ctx: Context<SetForfeitDestination>, forfeit_destination: ForfeitDestination) -> Result<()> {
+        instructions::admin::set_forfeit_destination(ctx, forfeit_destination)
+    }
+
+    pub fn set_min_active_days(ctx: Context<UpdateProgramConfig>, min_active_days: u8) -> Result<()> {
+        instructions::admin::set_min_active_days(ctx, min_active_days)
+    }
+
+    pub fn set_bonus_cap(ctx: Context<UpdateProgramConfig>, bonus_cap_ratio_bps: u64) -> Result<()> {
+        instructions::admin::set_bonus_cap(ctx, bonus_cap_ratio_bps)
+    }
+
+    pub fn set_bonus_rounding(ctx: Context<UpdateProgramConfig>, round_bonus: bool, bonus_precision: u8) -> Result<()> {
+        instructions::admin::set_bonus_rounding(ctx, round_bonus, bonus_precision)
+    }
+
+    pub fn deprecate_program(ctx: Context<UpdateProgramConfig>) -> Result<()> {
+        instructions::admin::deprecate_program(ctx)
+    }
+
+    pub fn set_pow_difficulty(ctx: Context<UpdateProgramConfig>, pow_difficulty: u8) -> Result<()> {
+        instructions::admin::set_pow_difficulty(ctx, pow_difficulty)
+    }
+
+    pub fn set_block_extra_sessions(ctx: Context<UpdateProgramConfig>, block_extra_sessions: bool) -> Result<()> {
+        instructions::admin::set_block_extra_sessions(ctx, block_extra_sessions)
+    }
+
+    pub fn set_streak_on_start(ctx: Context<UpdateProgramConfig>, streak_on_start: bool) -> Result<()> {
+        instructions::admin::set_streak_on_start(ctx, streak_on_start)
+    }
+
+    pub fn set_challenge_window(
+        ctx: Context<UpdateProgramConfig>,
+        challenge_start: i64,
+        challenge_end: i64,
+        challenge_bonus_bps: u64,
+    ) -> Result<()> {
+        instructions::admin::set_challenge_window(ctx, challenge_start, challenge_end, challenge_bonus_bps)
+    }
+
+    pub fn boost_reward_rate(ctx: Context<UpdateProgramConfig>, new_rate_bps: u64, until_timestamp: i64) -> Result<()> {
+        instructions::admin::boost_reward_rate(ctx, new_rate_bps, until_timestamp)
+    }
+
+    pub fn set_required_focus_ticks(ctx: Context<UpdateProgramConfig>, required_focus_ticks: u32) -> Result<()> {
+        instructions::admin::set_required_focus_ticks(ctx, required_focus_ticks)
+    }
+
+    pub fn add_authorized_caller(ctx: Context<UpdateProgramConfig>, program_id: Pubkey) -> Result<()> {
+        instructions::admin::add_authorized_caller(ctx, program_id)
+    }
+
+    pub fn remove_authorized_caller(ctx: Context<UpdateProgramConfig>, program_id: Pubkey) -> Result<()> {
+        instructions::admin::remove_authorized_caller(ctx, program_id)
+    }
+
+    pub fn set_treasury(ctx: Context<SetTreasury>) -> Result<()> {
+        instructions::admin::set_treasury(ctx)
+    }
+
+    pub fn set_min_commitment_days(ctx: Context<UpdateProgramConfig>, min_commitment_days: u8) -> Result<()> {
+        instructions::admin::set_min_commitment_days(ctx, min_commitment_days)
+    }
+
+    pub fn audit_total_staked<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateProgramConfig<'info>>,
+        apply: bool,
+    ) -> Result<u64> {
+        instructions::admin::audit_total_staked(ctx, apply)
+    }
+
+    pub fn set_program_paused(ctx: Context<UpdateProgramConfig>, paused: bool) -> Result<()> {
+        instructions::admin::set_program_paused(ctx, paused)
+    }
+
+    pub fn grant_downtime_credit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateProgramConfig<'info>>,
+    ) -> Result<()> {
+        instructions::admin::grant_downtime_credit(ctx)
     }
 
      pub fn create_user_profile(ctx: Context<CreateUserProfile>) -> Result<()> {
         instructions::user::create_user_profile(ctx)
     }
 
+    pub fn set_daily_minutes_goal(ctx: Context<UpdateUserProfile>, daily_minutes_goal: u16) -> Result<()> {
+        instructions::user::set_daily_minutes_goal(ctx, daily_minutes_goal)
+    }
+
+    pub fn set_referrer(ctx: Context<SetReferrer>, referrer: Pubkey) -> Result<()> {
+        instructions::user::set_referrer(ctx, referrer)
+    }
+
+    pub fn set_reminder_prefs(
+        ctx: Context<UpdateUserProfile>,
+        reminder_hour: u8,
+        reminders_enabled: bool,
+    ) -> Result<()> {
+        instructions::user::set_reminder_prefs(ctx, reminder_hour, reminders_enabled)
+    }
+
       pub fn create_commitment(
         ctx: Context<CreateCommitment>,
         commitment_id: u64,
@@ -39,17 +280,259 @@ pub mod f0x01 {
         instructions::commitment::create_commitment(ctx, commitment_id, amount, sessions_per_day, total_days)
     }
 
-     pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    pub fn create_commitment_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateCommitmentBatch<'info>>,
+        phases: Vec<CommitmentPhase>,
+        total_amount: u64,
+    ) -> Result<()> {
+        instructions::commitment::create_commitment_batch(ctx, phases, total_amount)
+    }
+
+    pub fn create_sponsored_commitment(
+        ctx: Context<CreateSponsoredCommitment>,
+        commitment_id: u64,
+        amount: u64,
+        sessions_per_day: u8,
+        total_days: u8,
+    ) -> Result<()> {
+        instructions::commitment::create_sponsored_commitment(ctx, commitment_id, amount, sessions_per_day, total_days)
+    }
+
+     pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
         instructions::commitment::claim_rewards(ctx)
     }
 
+    pub fn claim_all_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimAllRewards<'info>>) -> Result<()> {
+        instructions::commitment::claim_all_rewards(ctx)
+    }
+
+    pub fn set_keep_session_records(ctx: Context<UpdateCommitment>, keep_session_records: bool) -> Result<()> {
+        instructions::commitment::set_keep_session_records(ctx, keep_session_records)
+    }
+
+    pub fn lock_reward_rate(ctx: Context<UpdateCommitment>) -> Result<()> {
+        instructions::commitment::lock_reward_rate(ctx)
+    }
+
+    pub fn set_auto_renew(ctx: Context<UpdateCommitment>, auto_renew: bool) -> Result<()> {
+        instructions::commitment::set_auto_renew(ctx, auto_renew)
+    }
+
+    pub fn set_reminder_offset(ctx: Context<UpdateCommitment>, reminder_offset_secs: u32) -> Result<()> {
+        instructions::commitment::set_reminder_offset(ctx, reminder_offset_secs)
+    }
+
+    pub fn set_session_delegate(ctx: Context<UpdateCommitment>, delegate: Pubkey) -> Result<()> {
+        instructions::commitment::set_session_delegate(ctx, delegate)
+    }
+
+    pub fn auto_renew_commitment(ctx: Context<AutoRenewCommitment>) -> Result<()> {
+        instructions::commitment::auto_renew_commitment(ctx)
+    }
+
+    pub fn checkpoint_commitment(ctx: Context<CheckpointCommitment>) -> Result<()> {
+        instructions::commitment::checkpoint_commitment(ctx)
+    }
+
+    pub fn sponsor_commitment(ctx: Context<SponsorCommitment>, amount: u64) -> Result<()> {
+        instructions::commitment::sponsor_commitment(ctx, amount)
+    }
+
+    pub fn cancel_commitment(ctx: Context<CancelCommitment>) -> Result<()> {
+        instructions::commitment::cancel_commitment(ctx)
+    }
+
+    pub fn exit_on_deprecation(ctx: Context<ExitOnDeprecation>) -> Result<()> {
+        instructions::commitment::exit_on_deprecation(ctx)
+    }
+
+    pub fn revive_commitment(ctx: Context<ReviveCommitment>) -> Result<()> {
+        instructions::commitment::revive_commitment(ctx)
+    }
+
+    pub fn lock_commitment(ctx: Context<UpdateCommitment>) -> Result<()> {
+        instructions::commitment::lock_commitment(ctx)
+    }
+
+    pub fn set_auto_stake_on_milestone(ctx: Context<UpdateCommitment>, auto_stake_on_milestone: bool) -> Result<()> {
+        instructions::commitment::set_auto_stake_on_milestone(ctx, auto_stake_on_milestone)
+    }
+
+    pub fn set_fail_threshold(ctx: Context<UpdateCommitment>, fail_threshold_bps: u64) -> Result<()> {
+        instructions::commitment::set_fail_threshold(ctx, fail_threshold_bps)
+    }
+
+    pub fn set_skip_weekends(ctx: Context<SetSkipWeekends>, skip_weekends: bool, day_offset_secs: i32) -> Result<()> {
+        instructions::commitment::set_skip_weekends(ctx, skip_weekends, day_offset_secs)
+    }
+
+    pub fn set_focus_window(ctx: Context<UpdateCommitment>, allowed_start_hour: u8, allowed_end_hour: u8) -> Result<()> {
+        instructions::commitment::set_focus_window(ctx, allowed_start_hour, allowed_end_hour)
+    }
+
+    pub fn set_session_slots(ctx: Context<UpdateCommitment>, slot_start_hours: Vec<u8>, slot_end_hours: Vec<u8>) -> Result<()> {
+        instructions::commitment::set_session_slots(ctx, slot_start_hours, slot_end_hours)
+    }
+
     //session management
     pub fn start_session(ctx: Context<StartSession>, session_id: u64) -> Result<()> {
         instructions::session::start_session(ctx, session_id)
     }
 
-    pub fn complete_session(ctx: Context<CompleteSession>) -> Result<()> {
-        instructions::session::complete_session(ctx)
+    pub fn complete_session(ctx: Context<CompleteSession>, pow_nonce: u64, platform: Option<u8>) -> Result<()> {
+        instructions::session::complete_session(ctx, pow_nonce, platform)
+    }
+
+    pub fn verify_session_events(
+        ctx: Context<VerifySessionEvents>,
+        events_root: [u8; 32],
+        tick_count: u32,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::session::verify_session_events(ctx, events_root, tick_count, proof)
+    }
+
+    pub fn refresh_daily_counter<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefreshDailyCounter<'info>>,
+    ) -> Result<()> {
+        instructions::session::refresh_daily_counter(ctx)
+    }
+
+    //global stats
+    pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+        instructions::stats::initialize_global_stats(ctx)
+    }
+
+    pub fn rollover_period_stats(ctx: Context<RolloverPeriodStats>) -> Result<()> {
+        instructions::stats::rollover_period_stats(ctx)
+    }
+
+    pub fn auto_tune_reward_rate(ctx: Context<AutoTuneRewardRate>) -> Result<u64> {
+        instructions::stats::auto_tune_reward_rate(ctx)
+    }
+
+    //reward pool
+    pub fn initialize_reward_pool(ctx: Context<InitializeRewardPool>) -> Result<()> {
+        instructions::reward_pool::initialize_reward_pool(ctx)
+    }
+
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        instructions::reward_pool::fund_reward_pool(ctx, amount)
+    }
+
+    pub fn claim_first_session_reward(ctx: Context<ClaimFirstSessionReward>) -> Result<()> {
+        instructions::reward_pool::claim_first_session_reward(ctx)
+    }
+
+    pub fn drain_reward_pool_dust(ctx: Context<DrainRewardPoolDust>, amount: u64) -> Result<()> {
+        instructions::reward_pool::drain_reward_pool_dust(ctx, amount)
+    }
+
+    //streak airdrops
+    pub fn initialize_airdrop_pool(ctx: Context<InitializeAirdropPool>) -> Result<()> {
+        instructions::airdrop::initialize_airdrop_pool(ctx)
+    }
+
+    pub fn fund_airdrop_pool(ctx: Context<FundAirdropPool>, amount: u64) -> Result<()> {
+        instructions::airdrop::fund_airdrop_pool(ctx, amount)
+    }
+
+    pub fn claim_streak_airdrop(ctx: Context<ClaimStreakAirdrop>, tier: u8) -> Result<()> {
+        instructions::airdrop::claim_streak_airdrop(ctx, tier)
+    }
+
+    //weekly leagues
+    pub fn reset_weekly_stats(ctx: Context<ResetWeeklyStats>) -> Result<()> {
+        instructions::league::reset_weekly_stats(ctx)
+    }
+
+    //mint migration
+    pub fn migrate_mint(ctx: Context<MigrateMint>, ratio_bps: u64) -> Result<()> {
+        instructions::migration::migrate_mint(ctx, ratio_bps)
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        instructions::migration::migrate_vault(ctx)
+    }
+
+    //habit oracle
+    pub fn post_habit_attestation(ctx: Context<PostHabitAttestation>, days_attested: u16, days_completed: u16) -> Result<()> {
+        instructions::oracle::post_habit_attestation(ctx, days_attested, days_completed)
+    }
+
+    //pausing
+    pub fn pause_commitment(ctx: Context<PauseCommitment>) -> Result<()> {
+        instructions::pause::pause_commitment(ctx)
+    }
+
+    pub fn resume_commitment(ctx: Context<PauseCommitment>) -> Result<()> {
+        instructions::pause::resume_commitment(ctx)
+    }
+
+    pub fn pause_all_commitments<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PauseAllCommitments<'info>>,
+    ) -> Result<()> {
+        instructions::pause::pause_all_commitments(ctx)
+    }
+
+    //accountability partners
+    pub fn register_for_partner(ctx: Context<RegisterForPartner>, timezone_offset_mins: i16) -> Result<()> {
+        instructions::partner::register_for_partner(ctx, timezone_offset_mins)
+    }
+
+    pub fn match_partner(ctx: Context<MatchPartner>) -> Result<()> {
+        instructions::partner::match_partner(ctx)
+    }
+
+    //read-only views
+    pub fn next_session_eligible_at(ctx: Context<CommitmentRateView>) -> Result<i64> {
+        instructions::views::next_session_eligible_at(ctx)
+    }
+
+    pub fn claimed_reward_rate(ctx: Context<CommitmentView>) -> Result<u64> {
+        instructions::views::claimed_reward_rate(ctx)
+    }
+
+    pub fn is_expiring_soon(ctx: Context<CommitmentView>, within_secs: i64) -> Result<ExpiryStatus> {
+        instructions::views::is_expiring_soon(ctx, within_secs)
+    }
+
+    pub fn sessions_to_next_tier(ctx: Context<CommitmentView>) -> Result<TierProgress> {
+        instructions::views::sessions_to_next_tier(ctx)
+    }
+
+    pub fn can_complete_session(ctx: Context<SessionCompletionView>) -> Result<CompletionCheck> {
+        instructions::views::can_complete_session(ctx)
+    }
+
+    pub fn effective_reward_rate(ctx: Context<CommitmentRateView>) -> Result<u64> {
+        instructions::views::effective_reward_rate(ctx)
+    }
+
+    pub fn verify_session_count<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CommitmentRateView<'info>>,
+    ) -> Result<SessionCountCheck> {
+        instructions::views::verify_session_count(ctx)
+    }
+
+    pub fn preview_claim(ctx: Context<ClaimPreviewView>) -> Result<ClaimPreview> {
+        instructions::views::preview_claim(ctx)
+    }
+
+    pub fn get_dashboard<'info>(ctx: Context<'_, '_, 'info, 'info, DashboardView<'info>>) -> Result<Dashboard> {
+        instructions::views::get_dashboard(ctx)
+    }
+
+    pub fn export_commitment(ctx: Context<CommitmentView>) -> Result<Vec<u8>> {
+        instructions::views::export_commitment(ctx)
+    }
+
+    pub fn check_solvency(ctx: Context<SolvencyView>) -> Result<SolvencyReport> {
+        instructions::views::check_solvency(ctx)
+    }
+
+    pub fn reward_curve_bonus(ctx: Context<ProgramConfigView>, amount_staked: u64, success_bps: u64) -> Result<u64> {
+        instructions::views::reward_curve_bonus(ctx, amount_staked, success_bps)
     }
 
 }
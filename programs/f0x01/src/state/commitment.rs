@@ -1,4 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::FocusProgram;
+use crate::utils::{extend_perfect_day_streak, policy_reward_rate};
 
 #[account]
 pub struct FocusCommitment {
@@ -13,8 +16,532 @@ pub struct FocusCommitment {
     pub is_active: bool, //1
     pub last_session_timestamp: i64, //8
     pub sessions_completed_today: u8, //1
+    pub is_paused: bool, //1
+    pub paused_at: i64, //8
+    pub pause_count: u8, //1
+    pub keep_session_records: bool, //1
+    pub sessions_completed: u16, //2
+    pub total_focus_secs: u64, //8
+    pub accountability_partner: Pubkey, // partner's commitment, default if unmatched (32)
+    pub downtime_credited_secs: i64, // program downtime already added to this commitment (8)
+    pub locked_reward_rate_bps: u64, // program base rate when the commitment was created (8)
+    pub rate_locked: bool, // claim with locked_reward_rate_bps instead of the current rate (1)
+    pub next_session_id: u64, // id (and PDA seed) the next SessionRecord must use (8)
+    pub reserved_bonus: u64, // share of outstanding_bonus_liability held for this commitment (8)
+    pub allowed_start_hour: u8, // UTC hour sessions may start from (1)
+    pub allowed_end_hour: u8, // UTC hour sessions must start before, equal to allowed_start_hour = no window (1)
+    pub sponsor: Pubkey, // who staked and receives the payout, equal to user unless sponsored (32)
+    pub auto_stake_on_milestone: bool, // restake part of earned rewards here on streak milestones (1)
+    pub fail_threshold_bps: u64, // completion below this is a failure, DEFAULT_FAIL_THRESHOLD_BPS unless raised (8)
+    pub effective_reward_rate_used: u64, // rate claim_rewards applied, locked or boosted, 0 until claimed (8)
+    pub revival_count: u8, // times revived after the deadline, capped at MAX_REVIVALS_PER_COMMITMENT (1)
+    pub creation_slot: u64, // slot the commitment was created in, no session can predate it (8)
+    pub free_miss_used: bool, // claim_rewards forgave one missed session (1)
+    pub vault_migrated: bool, // stake lives in the migrated_vault PDA in the new mint (1)
+    pub is_locked: bool, // parameter-changing instructions are rejected (1)
+    pub auto_renew: bool, // auto_renew_commitment may restart the term with the same stake (1)
+    pub bonus_sessions: u16, // sessions completed after sessions_completed reached the expected total (2)
+    pub delegate: Pubkey, // key that may also start and complete sessions, default = none (32)
+    pub failure_success_bps: u16, // success rate of a claim below the failure threshold, 0 unless failed (2)
+    pub failure_sessions_missed: u16, // expected sessions not completed in the failed term (2)
+    pub failure_days_short: u8, // sessions_missed in whole days' worth, rounded up (1)
+    pub skip_weekends: bool, // Saturdays and Sundays don't count toward the expected sessions (1)
+    pub day_offset_secs: i32, // the user's UTC offset, used to tell which days are weekends (4)
+    pub active_day_flags: u32, // bit i set once a session started on commitment day i completes (4)
+    pub partial_credit_bps: u64, // credit from sessions ended between min_credit_secs and the full duration, in bps of a session (8)
+    pub reminder_offset_secs: u32, // how long before each commitment day ends notifiers should nudge the user, 0 = no nudge (4)
+    pub last_completed_start: i64, // start_timestamp of the latest completed session, 0 = none yet (8)
+    pub auto_failed: bool, // can no longer reach the program's auto-fail threshold; claimable before the deadline (1)
+    pub slot_count: u8, // ordered session slots per day, one per session; 0 = no slots (1)
+    pub slot_start_hours: [u8; MAX_SESSIONS_PER_DAY as usize], // UTC hour each slot opens (10)
+    pub slot_end_hours: [u8; MAX_SESSIONS_PER_DAY as usize], // UTC hour each slot closes, before start = wraps past midnight (10)
+    pub perfect_day_streak: u8, // consecutive commitment days with every daily session done, ending at last_perfect_day (1)
+    pub best_perfect_day_streak: u8, // longest perfect_day_streak this term, paid on at claim (1)
+    pub last_perfect_day: u8, // commitment day of the latest perfect day, meaningless while the streak is 0 (1)
+    pub checkpoint_count: u8, // snapshots recorded by checkpoint_commitment this term (1)
+    pub checkpoints: [ProgressCheckpoint; MAX_CHECKPOINTS], // the first checkpoint_count are recorded (12 * 8)
+    pub sponsored_amount: u64, // tokens gifted into the vault by sponsor_commitment, on top of amount_staked (8)
+    pub early_sessions: u16, // sessions completed before the program's early_bird_hour, local time (2)
+    pub closed_full_sessions: u16, // full sessions this term whose SessionRecord complete_session closed (2)
+    pub closed_partial_credit_bps: u64, // partial credit this term from SessionRecords complete_session closed (8)
+}
+
+// progress at one checkpoint_commitment call
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ProgressCheckpoint {
+    pub timestamp: i64, //8
+    pub sessions_completed: u16, //2
+    pub expected_sessions: u16, // sessions expected over the commitment days that had ended (2)
+}
+
+impl ProgressCheckpoint {
+    pub const SPACE: usize = 8 + 2 + 2;
 }
 
 impl FocusCommitment {
-    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 1 + 1 + 8 + 1 + 1 + 8 + 1;
-}
\ No newline at end of file
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 1 + 1 + 8 + 1 + 1 + 8 + 1 + 1 + 8 + 1 + 1 + 2 + 8 + 32 + 8 + 8 + 1 + 8 + 8 + 1 + 1 + 32 + 1 + 8 + 8 + 1 + 8 + 1 + 1 + 1 + 1 + 2 + 32 + 2 + 2 + 1 + 1 + 4 + 4 + 8 + 4 + 8 + 1 + 1 + 10 + 10 + 1 + 1 + 1 + 1 + ProgressCheckpoint::SPACE * MAX_CHECKPOINTS + 8 + 2 + 2 + 8;
+
+    // moves the commitment's timeline forward so time spent paused doesn't count
+    // against the deadline. `days_completed` is clamped to the shifted current day; it
+    // never needs to move back further, which would hand out a fresh daily allowance
+    pub fn shift_timeline(&mut self, secs: i64, current_timestamp: i64) {
+        self.start_timestamp += secs;
+        let current_day = self.days_elapsed(current_timestamp).clamp(0, u8::MAX as i64) as u8;
+        self.days_completed = self.days_completed.min(current_day);
+    }
+
+    // starts a fresh term of the same length and stake from `current_timestamp`
+    pub fn restart_term(&mut self, current_timestamp: i64, total_downtime_secs: i64) {
+        self.start_timestamp = current_timestamp;
+        self.days_completed = 0;
+        self.last_session_timestamp = 0;
+        self.sessions_completed_today = 0;
+        self.sessions_completed = 0;
+        self.bonus_sessions = 0;
+        self.active_day_flags = 0;
+        self.partial_credit_bps = 0;
+        self.auto_failed = false;
+        self.perfect_day_streak = 0;
+        self.best_perfect_day_streak = 0;
+        self.last_perfect_day = 0;
+        self.checkpoint_count = 0;
+        self.early_sessions = 0;
+        self.closed_full_sessions = 0;
+        self.closed_partial_credit_bps = 0;
+        self.downtime_credited_secs = total_downtime_secs;
+    }
+
+    // the owner, or the session delegate if one is set
+    pub fn can_run_sessions(&self, signer: &Pubkey) -> bool {
+        *signer == self.user || (self.delegate != Pubkey::default() && *signer == self.delegate)
+    }
+
+    // MAX_COMMITMENT_DAYS keeps every day index inside the 32 flag bits
+    pub fn mark_active_day(&mut self, day: i64) {
+        if (0..32).contains(&day) {
+            self.active_day_flags |= 1 << day;
+        }
+    }
+
+    // extends the perfect-day streak when `day` follows the last perfect day, otherwise a
+    // day was missed or cut short in between and the streak starts over
+    pub fn record_perfect_day(&mut self, day: i64) {
+        let day = day.clamp(0, u8::MAX as i64) as u8;
+        self.perfect_day_streak = extend_perfect_day_streak(self.perfect_day_streak, self.last_perfect_day, day);
+        self.last_perfect_day = day;
+        self.best_perfect_day_streak = self.best_perfect_day_streak.max(self.perfect_day_streak);
+    }
+
+    // distinct commitment days with at least one completed session
+    pub fn active_days(&self) -> u8 {
+        self.active_day_flags.count_ones() as u8
+    }
+
+    // the part of the gifted tokens paid out alongside `returned` of the stake: gifts are
+    // returned in the same proportion as the stake and forfeited with the rest of it
+    pub fn gift_returned(&self, returned: u64) -> u64 {
+        if self.amount_staked == 0 {
+            return 0;
+        }
+        (self.sponsored_amount as u128 * returned as u128 / self.amount_staked as u128) as u64
+    }
+
+    pub fn is_sponsored(&self) -> bool {
+        self.sponsor != self.user
+    }
+
+    // a commitment that opted into lock_reward_rate keeps its rate whatever the program's policy
+    pub fn reward_rate_bps(&self, program: &FocusProgram, current_timestamp: i64) -> u64 {
+        if self.rate_locked {
+            return self.locked_reward_rate_bps;
+        }
+        policy_reward_rate(
+            program.rate_policy,
+            self.locked_reward_rate_bps,
+            program.current_reward_rate_bps(current_timestamp),
+        )
+    }
+
+    // seed prefix of the token account currently holding this commitment's stake
+    pub fn vault_seed(&self) -> &'static [u8] {
+        if self.vault_migrated {
+            b"migrated_vault"
+        } else {
+            b"vault"
+        }
+    }
+
+    pub fn expected_sessions(&self) -> u16 {
+        self.expected_sessions_in(self.total_days as i64)
+    }
+
+    // sessions expected over the first `days` commitment days, at most the whole term
+    pub fn expected_sessions_in(&self, days: i64) -> u16 {
+        let days = days.clamp(0, self.total_days as i64);
+        let counted_days = if self.skip_weekends {
+            (0..days).filter(|day| !self.is_weekend_day(*day)).count() as u16
+        } else {
+            days as u16
+        };
+        self.sessions_per_day as u16 * counted_days
+    }
+
+    // when the next checkpoint may be taken: one every `interval_secs` from the start
+    pub fn next_checkpoint_at(&self, interval_secs: i64) -> i64 {
+        self.start_timestamp + (self.checkpoint_count as i64 + 1) * interval_secs
+    }
+
+    // whether `timestamp` falls before `early_bird_hour` in the user's local time. 0 = no
+    // early-bird hour, so midnight itself is never early
+    pub fn is_early_bird(&self, timestamp: i64, early_bird_hour: u8) -> bool {
+        let local_timestamp = timestamp + self.day_offset_secs as i64;
+        let local_hour = local_timestamp.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_HOUR;
+        local_hour < early_bird_hour as i64
+    }
+
+    // whether commitment day `day` starts on a Saturday or Sunday in the user's local time
+    pub fn is_weekend_day(&self, day: i64) -> bool {
+        let local_timestamp = self.start_timestamp + day * SECONDS_PER_DAY + self.day_offset_secs as i64;
+        (local_timestamp.div_euclid(SECONDS_PER_DAY) + EPOCH_WEEKDAY).rem_euclid(7) >= 5
+    }
+
+    pub fn sessions_exhausted(&self) -> bool {
+        self.sessions_completed >= self.expected_sessions()
+    }
+
+    // counts a completed session that started at `start_timestamp`. a session belongs to the
+    // day it started on, so once the counter has moved on to a later day today's count is
+    // left alone. once the expected total is reached further full sessions are bonus sessions,
+    // so sessions_completed never claims more than 100% completion
+    pub fn credit_session(&mut self, start_timestamp: i64, elapsed_secs: i64, is_full_session: bool) {
+        let session_day = self.days_elapsed(start_timestamp);
+        if session_day == self.days_completed as i64 {
+            self.sessions_completed_today += 1;
+            if self.sessions_completed_today == self.sessions_per_day {
+                self.record_perfect_day(session_day);
+            }
+        }
+        self.mark_active_day(session_day);
+        if !is_full_session {
+            self.partial_credit_bps = self.partial_credit_bps.checked_add(Self::partial_credit(elapsed_secs)).unwrap();
+        } else if self.sessions_exhausted() {
+            self.bonus_sessions += 1;
+        } else {
+            self.sessions_completed += 1;
+        }
+        self.total_focus_secs += elapsed_secs as u64;
+    }
+
+    // reverses credit_session. the day stays marked active, since another session may
+    // have been completed on it. a perfect day that loses a session ends the streak the day
+    // before; the term's best streak is kept, as an earlier streak may have reached it too
+    pub fn uncredit_session(&mut self, start_timestamp: i64, elapsed_secs: i64, is_full_session: bool) {
+        let session_day = self.days_elapsed(start_timestamp);
+        if session_day == self.days_completed as i64 {
+            if self.sessions_completed_today == self.sessions_per_day
+                && self.perfect_day_streak > 0
+                && self.last_perfect_day as i64 == session_day
+            {
+                self.perfect_day_streak -= 1;
+                self.last_perfect_day = self.last_perfect_day.saturating_sub(1);
+            }
+            self.sessions_completed_today = self.sessions_completed_today.saturating_sub(1);
+        }
+        if !is_full_session {
+            self.partial_credit_bps = self.partial_credit_bps.saturating_sub(Self::partial_credit(elapsed_secs));
+        } else if self.bonus_sessions > 0 {
+            self.bonus_sessions -= 1;
+        } else {
+            self.sessions_completed = self.sessions_completed.saturating_sub(1);
+        }
+        self.total_focus_secs = self.total_focus_secs.saturating_sub(elapsed_secs as u64);
+    }
+
+    // keeps the credit of a session whose record complete_session is about to close, so the
+    // strict recount at claim still counts it
+    pub fn record_closed_session(&mut self, elapsed_secs: i64, is_full_session: bool) {
+        if is_full_session {
+            self.closed_full_sessions = self.closed_full_sessions.saturating_add(1);
+        } else {
+            self.closed_partial_credit_bps = self.closed_partial_credit_bps.saturating_add(Self::partial_credit(elapsed_secs));
+        }
+    }
+
+    // a short session's share of a full session, in bps
+    pub fn partial_credit(elapsed_secs: i64) -> u64 {
+        elapsed_secs as u64 * BPS_DENOMINATOR / SESSION_DURATION_SECS as u64
+    }
+
+    // completed sessions as claim_rewards counts them: each commitment forgives one missed session
+    pub fn credited_sessions(&self) -> u16 {
+        if self.sessions_completed < self.expected_sessions() && !self.free_miss_used {
+            self.sessions_completed + 1
+        } else {
+            self.sessions_completed
+        }
+    }
+
+    // keeps a summary of a failed term for the user to look back on
+    pub fn record_failure(&mut self, success_bps: u64) {
+        // free_miss_used is only set by the claim that applied it, so it counts as completed
+        let credited = self.sessions_completed + self.free_miss_used as u16;
+        let sessions_missed = self.expected_sessions().saturating_sub(credited);
+        self.failure_success_bps = success_bps.min(BPS_DENOMINATOR) as u16;
+        self.failure_sessions_missed = sessions_missed;
+        self.failure_days_short = sessions_missed.div_ceil(self.sessions_per_day as u16).min(u8::MAX as u16) as u8;
+    }
+
+    // credited sessions plus partial credit, in bps of a single session
+    pub fn credited_session_bps(&self) -> u64 {
+        self.credited_sessions() as u64 * BPS_DENOMINATOR + self.partial_credit_bps
+    }
+
+    // the claim's success rate from this commitment's own sessions, at most 100%
+    pub fn success_bps(&self) -> u64 {
+        (self.credited_session_bps() / self.expected_sessions() as u64).min(BPS_DENOMINATOR)
+    }
+
+    // the success rate reached if every session slot left before the deadline is used
+    pub fn max_achievable_success_bps(&self, current_timestamp: i64) -> u64 {
+        let expected = self.expected_sessions() as u64;
+        let days_elapsed = self.days_elapsed(current_timestamp);
+        let remaining_sessions = if days_elapsed >= self.total_days as i64 {
+            0
+        } else {
+            let later_days = (self.total_days as i64 - days_elapsed - 1) as u64;
+            self.sessions_per_day.saturating_sub(self.sessions_today(current_timestamp)) as u64
+                + later_days * self.sessions_per_day as u64
+        };
+        let possible = (self.sessions_completed as u64 + remaining_sessions).min(expected);
+        let free_miss = (possible < expected && !self.free_miss_used) as u64;
+        (((possible + free_miss) * BPS_DENOMINATOR + self.partial_credit_bps) / expected).min(BPS_DENOMINATOR)
+    }
+
+    // whether this commitment can no longer reach the auto-fail threshold. the threshold is held
+    // to the commitment's own fail threshold, so an early claim lands on the partial refund it
+    // was bound for anyway. paused commitments and uncredited downtime can still extend the
+    // term, and too few active days would turn the refund into nothing, so those wait
+    pub fn should_auto_fail(&self, program: &FocusProgram, current_timestamp: i64) -> bool {
+        if program.auto_fail_threshold_bps == 0
+            || program.is_paused
+            || self.is_paused
+            || self.downtime_credited_secs < program.total_downtime_secs
+            || self.active_days() < program.required_active_days(self.total_days)
+        {
+            return false;
+        }
+        // a habit oracle attestation could still lift the rate, so assume a perfect one
+        let own_bps = self.max_achievable_success_bps(current_timestamp);
+        let best_bps = if program.habit_oracle == Pubkey::default() {
+            own_bps
+        } else {
+            (own_bps * (BPS_DENOMINATOR - program.oracle_weight_bps) + BPS_DENOMINATOR * program.oracle_weight_bps)
+                / BPS_DENOMINATOR
+        };
+        best_bps < program.auto_fail_threshold_bps.min(self.fail_threshold_bps)
+    }
+
+    // further full sessions needed before a claim reaches `tier_bps`, 0 once it has
+    pub fn sessions_to_tier(&self, tier_bps: u64) -> u16 {
+        let required_bps = tier_bps * self.expected_sessions() as u64;
+        required_bps.saturating_sub(self.credited_session_bps()).div_ceil(BPS_DENOMINATOR) as u16
+    }
+
+    pub fn deadline(&self) -> i64 {
+        self.start_timestamp + self.total_days as i64 * SECONDS_PER_DAY
+    }
+
+    // the deadline a term starting at `start_timestamp` would have, None if it overflows i64
+    pub fn checked_deadline(start_timestamp: i64, total_days: u8) -> Option<i64> {
+        (total_days as i64)
+            .checked_mul(SECONDS_PER_DAY)
+            .and_then(|duration| start_timestamp.checked_add(duration))
+    }
+
+    // the commitment's day index: whole days since start_timestamp, not UTC calendar days,
+    // so a commitment created just before midnight still gets a full first day. floors
+    // toward negative infinity so a timestamp before the start is never day 0
+    pub fn days_elapsed(&self, current_timestamp: i64) -> i64 {
+        (current_timestamp - self.start_timestamp).div_euclid(SECONDS_PER_DAY)
+    }
+
+    // moves the daily counter onto the current commitment day, resetting it on a new day
+    pub fn roll_over_day(&mut self, current_timestamp: i64) {
+        let days_elapsed = self.days_elapsed(current_timestamp);
+        if days_elapsed > self.days_completed as i64 {
+            self.days_completed = days_elapsed.min(u8::MAX as i64) as u8;
+            self.sessions_completed_today = 0;
+        }
+    }
+
+    // [allowed_start_hour, allowed_end_hour) in UTC; a start after the end wraps past midnight
+    pub fn is_within_focus_window(&self, current_timestamp: i64) -> bool {
+        if self.allowed_start_hour == self.allowed_end_hour {
+            return true;
+        }
+
+        is_within_hours(current_timestamp, self.allowed_start_hour, self.allowed_end_hour)
+    }
+
+    // with ordered slots, the next session takes the slot after the ones completed today
+    // and must start inside that slot's hours
+    pub fn is_within_next_slot(&self, current_timestamp: i64) -> bool {
+        if self.slot_count == 0 {
+            return true;
+        }
+
+        let slot = self.sessions_today(current_timestamp) as usize;
+        if slot >= self.slot_count as usize {
+            return false;
+        }
+        is_within_hours(current_timestamp, self.slot_start_hours[slot], self.slot_end_hours[slot])
+    }
+
+    // the final day may relax the gap so a user who fell behind can still catch up
+    pub fn min_session_gap_secs(&self, program: &FocusProgram, current_timestamp: i64) -> i64 {
+        let final_day = self.total_days as i64 - 1;
+        if program.catch_up_gap_secs > 0 && self.days_elapsed(current_timestamp) == final_day {
+            program.catch_up_gap_secs
+        } else {
+            MIN_SESSION_GAP_SECS
+        }
+    }
+
+    // sessions already done today, treating a counter left over from an earlier day as zero
+    pub fn sessions_today(&self, current_timestamp: i64) -> u8 {
+        if self.days_elapsed(current_timestamp) > self.days_completed as i64 {
+            0
+        } else {
+            self.sessions_completed_today
+        }
+    }
+
+    // earliest timestamp a new session may start, or NO_ELIGIBLE_SESSION once the
+    // commitment can't take any more sessions. a later phase of a batch starts at its
+    // start_timestamp
+    pub fn next_session_eligible_at(&self, program: &FocusProgram, current_timestamp: i64) -> i64 {
+        if !self.is_active || self.is_paused || self.auto_failed {
+            return NO_ELIGIBLE_SESSION;
+        }
+        if program.block_extra_sessions && self.sessions_exhausted() {
+            return NO_ELIGIBLE_SESSION;
+        }
+
+        // every check start_session makes only changes at a gap, a commitment day or,
+        // with a focus window or slots, a UTC hour boundary, so stepping through those
+        // finds the first time they all pass
+        let hourly = self.allowed_start_hour != self.allowed_end_hour || self.slot_count > 0;
+        let deadline = self.deadline();
+        let mut candidate = current_timestamp.max(self.start_timestamp);
+        while candidate < deadline {
+            if self.can_start_session_at(program, candidate) {
+                return candidate;
+            }
+
+            let mut next = self.start_timestamp + (self.days_elapsed(candidate) + 1) * SECONDS_PER_DAY;
+            if hourly {
+                next = next.min(candidate - candidate.rem_euclid(SECONDS_PER_HOUR) + SECONDS_PER_HOUR);
+            }
+            if self.last_session_timestamp > 0 {
+                for gap_secs in [MIN_SESSION_GAP_SECS, program.catch_up_gap_secs] {
+                    let gap_ends_at = self.last_session_timestamp + gap_secs;
+                    if gap_secs > 0 && gap_ends_at > candidate {
+                        next = next.min(gap_ends_at);
+                    }
+                }
+            }
+            candidate = next;
+        }
+
+        NO_ELIGIBLE_SESSION
+    }
+
+    // start_session's timing checks, for a session started at `timestamp`
+    fn can_start_session_at(&self, program: &FocusProgram, timestamp: i64) -> bool {
+        let days_elapsed = self.days_elapsed(timestamp);
+        (0..self.total_days as i64).contains(&days_elapsed)
+            && self.sessions_today(timestamp) < self.sessions_per_day
+            && self.is_within_focus_window(timestamp)
+            && self.is_within_next_slot(timestamp)
+            && (self.last_session_timestamp == 0
+                || timestamp - self.last_session_timestamp >= self.min_session_gap_secs(program, timestamp))
+    }
+}
+
+// [start_hour, end_hour) in UTC; a start after the end wraps past midnight
+fn is_within_hours(current_timestamp: i64, start_hour: u8, end_hour: u8) -> bool {
+    let hour = (current_timestamp.rem_euclid(SECONDS_PER_DAY) / SECONDS_PER_HOUR) as u8;
+    if start_hour < end_hour {
+        hour >= start_hour && hour < end_hour
+    } else {
+        hour >= start_hour || hour < end_hour
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitment(start_timestamp: i64, sessions_per_day: u8, total_days: u8) -> FocusCommitment {
+        let mut commitment = FocusCommitment::try_deserialize_unchecked(&mut &[0u8; FocusCommitment::SPACE][..]).unwrap();
+        commitment.start_timestamp = start_timestamp;
+        commitment.sessions_per_day = sessions_per_day;
+        commitment.total_days = total_days;
+        commitment
+    }
+
+    #[test]
+    fn sessions_spanning_the_day_boundary_count_toward_their_start_day() {
+        let start = 10 * SECONDS_PER_DAY;
+        let mut commitment = commitment(start, 2, 3);
+        // started half an hour before the end of day 0, completed on day 1
+        let session_start = start + SECONDS_PER_DAY - 1800;
+        let session_end = session_start + SESSION_DURATION_SECS;
+        commitment.credit_session(session_start, SESSION_DURATION_SECS, true);
+        assert_eq!(commitment.days_completed, 0);
+        assert_eq!(commitment.sessions_completed_today, 1);
+        assert_eq!(commitment.sessions_today(session_end), 0);
+        assert_eq!(commitment.active_day_flags, 0b01);
+
+        // day 1 starts with its full allowance
+        let next_start = session_start + SESSION_DURATION_SECS;
+        commitment.roll_over_day(next_start);
+        commitment.credit_session(next_start, SESSION_DURATION_SECS, true);
+        assert_eq!(commitment.days_completed, 1);
+        assert_eq!(commitment.sessions_today(next_start), 1);
+        assert_eq!(commitment.sessions_completed, 2);
+        assert_eq!(commitment.active_day_flags, 0b11);
+    }
+
+    #[test]
+    fn a_late_completion_leaves_a_later_days_counter_alone() {
+        let start = 10 * SECONDS_PER_DAY;
+        let mut commitment = commitment(start, 2, 3);
+        let session_start = start + SECONDS_PER_DAY - 1800;
+        // the counter moved onto day 1 before the day 0 session was completed
+        commitment.roll_over_day(start + SECONDS_PER_DAY);
+        commitment.credit_session(session_start, SESSION_DURATION_SECS, true);
+        assert_eq!(commitment.sessions_completed_today, 0);
+        assert_eq!(commitment.sessions_completed, 1);
+        assert_eq!(commitment.active_day_flags, 0b01);
+    }
+
+    #[test]
+    fn uncrediting_a_perfect_day_ends_the_streak_the_day_before() {
+        let start = 10 * SECONDS_PER_DAY;
+        let mut commitment = commitment(start, 1, 3);
+        commitment.credit_session(start, SESSION_DURATION_SECS, true);
+        let day_one = start + SECONDS_PER_DAY;
+        commitment.roll_over_day(day_one);
+        commitment.credit_session(day_one, SESSION_DURATION_SECS, true);
+        assert_eq!((commitment.perfect_day_streak, commitment.last_perfect_day), (2, 1));
+
+        commitment.uncredit_session(day_one, SESSION_DURATION_SECS, true);
+        assert_eq!((commitment.perfect_day_streak, commitment.last_perfect_day), (1, 0));
+        assert_eq!(commitment.sessions_completed_today, 0);
+        assert_eq!(commitment.best_perfect_day_streak, 2);
+        // crediting it again restores the streak
+        commitment.credit_session(day_one, SESSION_DURATION_SECS, true);
+        assert_eq!((commitment.perfect_day_streak, commitment.last_perfect_day), (2, 1));
+    }
+}
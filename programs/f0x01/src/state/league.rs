@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+
+// one user's archived session count for a finished league week. prizes are paid from
+// these by a separate distribution
+#[account]
+pub struct WeeklyLeagueEntry {
+    pub user: Pubkey, //32
+    pub bump: u8, //1
+    pub week: i64, // week index since the unix epoch (8)
+    pub sessions_completed: u32, //4
+}
+
+impl WeeklyLeagueEntry {
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 4;
+
+    // league weeks are fixed 7 day spans counted from the unix epoch
+    pub fn week_of(timestamp: i64) -> i64 {
+        timestamp.div_euclid(SECONDS_PER_WEEK)
+    }
+}
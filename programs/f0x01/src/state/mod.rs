@@ -10,5 +10,17 @@ pub use commitment::*;
 pub mod session;
 pub use session::*;
 
+pub mod partner;
+pub use partner::*;
 
+pub mod receipt;
+pub use receipt::*;
 
+pub mod stats;
+pub use stats::*;
+
+pub mod league;
+pub use league::*;
+
+pub mod oracle;
+pub use oracle::*;
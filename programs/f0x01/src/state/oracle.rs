@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+
+// a habit-tracking oracle's latest view of how many days a user completed in its own app
+#[account]
+pub struct HabitAttestation {
+    pub user: Pubkey, //32
+    pub bump: u8, //1
+    pub oracle: Pubkey, // who posted it, ignored once the program's oracle changes (32)
+    pub days_attested: u16, //2
+    pub days_completed: u16, //2
+    pub updated_at: i64, //8
+}
+
+impl HabitAttestation {
+    pub const SPACE: usize = 8 + 32 + 1 + 32 + 2 + 2 + 8;
+
+    pub fn completion_bps(&self) -> u64 {
+        if self.days_attested == 0 {
+            return 0;
+        }
+        self.days_completed as u64 * BPS_DENOMINATOR / self.days_attested as u64
+    }
+}
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct PartnerListing {
+    pub user: Pubkey, //32
+    pub bump: u8, //1
+    pub commitment: Pubkey, //32
+    pub daily_target: u8, // sessions per day of the listed commitment (1)
+    pub timezone_offset_mins: i16, // minutes east of UTC (2)
+    pub listed_at: i64, //8
+}
+
+impl PartnerListing {
+    pub const SPACE: usize = 8 + 32 + 1 + 32 + 1 + 2 + 8;
+}
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::state::HabitAttestation;
 
 #[account]
 pub struct FocusProgram {
@@ -6,10 +8,385 @@ pub struct FocusProgram {
     pub bump: u8, //1
     pub total_users: u64, //8
     pub total_staked: u64, //8
-    pub reward_rate: u64,  // reward multiplier for successful completion (8)
+    pub reward_rate_bps: u64,  // bonus for successful completion, in basis points of the stake (8)
     pub focus_token_mint: Pubkey, //32
+    pub min_commitment_days: u8, //1
+    pub is_paused: bool, //1
+    pub paused_at: i64, //8
+    pub total_downtime_secs: i64, // accumulated time spent paused, credited to commitments (8)
+    pub first_session_reward: u64, // one-time onboarding reward paid from the reward pool, 0 = off (8)
+    pub max_clock_drift_bps: u64, // allowed gap between clock and slot elapsed time, 0 = off (8)
+    pub boosted_rate_bps: u64, // promotional rate applied until boost_expiry (8)
+    pub boost_expiry: i64, //8
+    pub treasury: Pubkey, // token account that receives drained dust (32)
+    pub outstanding_bonus_liability: u64, // bonuses reserved for active commitments, paid from the reward pool (8)
+    pub required_focus_ticks: u32, // proven app ticks complete_session requires, 0 = off (4)
+    pub duration_tolerance_secs: i64, // slack taken off the 55 minute session requirement (8)
+    pub authorized_callers: [Pubkey; MAX_AUTHORIZED_CALLERS], // programs allowed to create commitments via CPI (32 * 4)
+    pub authorized_caller_count: u8, //1
+    pub referral_bonus_bps: u64, // paid to the referrer, in bps of the referee's stake, on a bonus-tier claim (8)
+    pub max_total_staked: u64, // cap on total_staked, 0 = uncapped (8)
+    pub streak_airdrop_amounts: [u64; 3], // payout per STREAK_AIRDROP_TIER_DAYS tier, 0 = tier off (8 * 3)
+    pub catch_up_gap_secs: i64, // session gap on a commitment's final day, 0 = use MIN_SESSION_GAP_SECS (8)
+    pub challenge_start: i64, //8
+    pub challenge_end: i64, //8
+    pub challenge_bonus_bps: u64, // extra bonus for commitments run entirely inside the challenge window, 0 = off (8)
+    pub streak_on_start: bool, // streaks extend when a session starts rather than when it completes (1)
+    pub legacy_token_mint: Pubkey, // mint replaced by migrate_mint, default if never migrated (32)
+    pub mint_migration_ratio_bps: u64, // new tokens per old token migrate_vault pays, in bps (8)
+    pub pow_difficulty: u8, // leading zero bits complete_session's proof-of-work needs, 0 = off (1)
+    pub is_deprecated: bool, // winding down: no new commitments, stakes exit in full (1)
+    pub round_bonus: bool, // round claim bonuses down to bonus_precision decimal places (1)
+    pub bonus_precision: u8, // decimal places of the token a rounded bonus keeps, 0 = whole tokens (1)
+    pub min_stake_tokens: u64, // minimum stake in whole tokens, scaled by the mint's decimals, 0 = off (8)
+    pub habit_oracle: Pubkey, // posts HabitAttestations claim_rewards may blend in, default = off (32)
+    pub oracle_weight_bps: u64, // share of the blended success rate taken from the oracle (8)
+    pub max_early_penalty_bps: u64, // cancel_commitment penalty on day one, shrinking with the days remaining (8)
+    pub mint_bonuses: bool, // claim_rewards mints bonuses through the mint_authority PDA instead of paying from the pool (1)
+    pub mint_authority_bump: u8, // bump of the mint_authority PDA (1)
+    pub mint_cap_per_epoch: u64, // most bonus tokens that may be minted in one epoch (8)
+    pub mint_epoch: u64, // epoch epoch_minted is counted for (8)
+    pub epoch_minted: u64, // bonus tokens minted so far in mint_epoch (8)
+    pub block_extra_sessions: bool, // reject sessions once a commitment's expected sessions are done, instead of counting them as bonus sessions (1)
+    pub bonus_cap_ratio_bps: u64, // largest claim bonus as a share of the stake, 0 = uncapped (8)
+    pub forfeit_destination: ForfeitDestination, // where the unrefunded part of a failed stake goes (1)
+    pub charity: Pubkey, // token account forfeits go to under ForfeitDestination::Charity (32)
+    pub min_active_days: u8, // distinct days with a completed session needed for any refund, 0 = off (1)
+    pub min_credit_secs: i64, // shortest session given partial credit, 0 = only full sessions count (8)
+    pub bonus_source: FundingSource, // pays claim bonuses when they aren't minted, always the reward pool (1)
+    pub principal_source: FundingSource, // pays the stake returned at or above the failure threshold (1)
+    pub partial_refund_source: FundingSource, // pays the partial refund below the failure threshold (1)
+    pub auto_fail_threshold_bps: u64, // commitments that can't reach this rate any more are failed early, 0 = off (8)
+    pub strict_claim_verification: bool, // claim_rewards recounts sessions from the SessionRecords passed to it (1)
+    pub streak_grace_days: u8, // days after a claim a new commitment can start without breaking the streak, 0 = off (1)
+    pub max_backdate_secs: i64, // how far in the past client-supplied session starts may be (8)
+    pub claim_cooldown_secs: i64, // shortest time between one user's bonus-paying claims, 0 = off (8)
+    pub curve_floor_bps: u64, // success rate the continuous bonus starts rising from (8)
+    pub curve_ceiling_bps: u64, // success rate the continuous bonus reaches curve_max_bonus_bps at (8)
+    pub curve_max_bonus_bps: u64, // continuous bonus at the ceiling, in bps of the stake; 0 = tiered rewards (8)
+    pub tune_min_rate_bps: u64, // lowest reward_rate_bps auto_tune_reward_rate may set (8)
+    pub tune_max_rate_bps: u64, // highest reward_rate_bps auto_tune_reward_rate may set (8)
+    pub tune_max_step_bps: u64, // most one auto_tune_reward_rate call moves the rate, 0 = tuning off (8)
+    pub last_rate_tune_at: i64, //8
+    pub perfect_streak_bonus_bps: u64, // bonus per day of a term's best perfect-day streak, in bps of the stake, 0 = off (8)
+    pub min_stake_per_day: u64, // smallest stake per commitment day, in raw token units, 0 = off (8)
+    pub checkpoint_interval_secs: i64, // time between a commitment's progress checkpoints, 0 = off (8)
+    pub ms_per_slot: u64, // the cluster's slot time, used to turn session durations into slot counts (8)
+    pub early_bird_hour: u8, // local hour sessions must complete before to count as early, 0 = off (1)
+    pub early_bird_bonus_bps: u64, // bonus when every expected session is early, in bps of the stake (8)
+    pub rate_policy: RatePolicy, // which reward rate a claim uses once the rate changed after creation (1)
+    pub active_commitments: u64, // commitments created and not yet settled, the set audit_total_staked must see (8)
 }
 
-impl FocusProgram {
+// FocusProgram as first deployed, with the reward rate in whole percents. every later field
+// was appended after focus_token_mint, so these are also the first fields of today's layout
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyFocusProgram {
+    pub authority: Pubkey, //32
+    pub bump: u8, //1
+    pub total_users: u64, //8
+    pub total_staked: u64, //8
+    pub reward_rate: u64, // whole percent of the stake (8)
+    pub focus_token_mint: Pubkey, //32
+}
+
+impl LegacyFocusProgram {
     pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 8 + 32;
-}
\ No newline at end of file
+
+    pub fn reward_rate_bps(&self) -> u64 {
+        self.reward_rate.checked_mul(BPS_PER_PERCENT).unwrap()
+    }
+}
+
+// receiver of the part of a failed commitment's stake that isn't refunded
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ForfeitDestination {
+    Treasury,
+    RewardPool,
+    Charity,
+}
+
+// account a claim payout is drawn from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FundingSource {
+    Vault,
+    RewardPool,
+}
+
+// reward rate an in-flight commitment claims at after reward_rate_bps or a boost changed it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum RatePolicy {
+    LockAtCreation,
+    UseCurrent,
+    BetterOfBoth,
+}
+
+impl FocusProgram {
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 8 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 4 + 8 + 32 * MAX_AUTHORIZED_CALLERS + 1 + 8 + 8 + 8 * 3 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 1 + 1 + 1 + 1 + 8 + 32 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 1 + 32 + 1 + 8 + 1 + 1 + 1 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 8;
+
+    // the configuration a new program starts with: every field after focus_token_mint
+    pub fn apply_default_config(&mut self) {
+        self.min_commitment_days = 0;
+        self.is_paused = false;
+        self.paused_at = 0;
+        self.total_downtime_secs = 0;
+        self.first_session_reward = 0;
+        self.max_clock_drift_bps = DEFAULT_MAX_CLOCK_DRIFT_BPS;
+        self.boosted_rate_bps = 0;
+        self.boost_expiry = 0;
+        self.treasury = Pubkey::default();
+        self.outstanding_bonus_liability = 0;
+        self.required_focus_ticks = 0;
+        self.duration_tolerance_secs = 0;
+        self.authorized_callers = [Pubkey::default(); MAX_AUTHORIZED_CALLERS];
+        self.authorized_caller_count = 0;
+        self.referral_bonus_bps = 0;
+        self.max_total_staked = 0;
+        self.streak_airdrop_amounts = [0; 3];
+        self.catch_up_gap_secs = 0;
+        self.challenge_start = 0;
+        self.challenge_end = 0;
+        self.challenge_bonus_bps = 0;
+        self.streak_on_start = false;
+        self.legacy_token_mint = Pubkey::default();
+        self.mint_migration_ratio_bps = 0;
+        self.pow_difficulty = 0;
+        self.is_deprecated = false;
+        self.round_bonus = false;
+        self.bonus_precision = 0;
+        self.min_stake_tokens = 0;
+        self.habit_oracle = Pubkey::default();
+        self.oracle_weight_bps = 0;
+        self.max_early_penalty_bps = DEFAULT_MAX_EARLY_PENALTY_BPS;
+        self.mint_bonuses = false;
+        self.mint_authority_bump = 0;
+        self.mint_cap_per_epoch = 0;
+        self.mint_epoch = 0;
+        self.epoch_minted = 0;
+        self.block_extra_sessions = false;
+        self.bonus_cap_ratio_bps = 0;
+        self.forfeit_destination = ForfeitDestination::Treasury;
+        self.charity = Pubkey::default();
+        self.min_active_days = 0;
+        self.min_credit_secs = 0;
+        self.bonus_source = FundingSource::RewardPool;
+        self.principal_source = FundingSource::Vault;
+        self.partial_refund_source = FundingSource::Vault;
+        self.auto_fail_threshold_bps = 0;
+        self.strict_claim_verification = false;
+        self.streak_grace_days = 0;
+        self.max_backdate_secs = DEFAULT_MAX_BACKDATE_SECS;
+        self.claim_cooldown_secs = 0;
+        self.curve_floor_bps = 0;
+        self.curve_ceiling_bps = 0;
+        self.curve_max_bonus_bps = 0;
+        self.tune_min_rate_bps = 0;
+        self.tune_max_rate_bps = 0;
+        self.tune_max_step_bps = 0;
+        self.last_rate_tune_at = 0;
+        self.perfect_streak_bonus_bps = 0;
+        self.min_stake_per_day = 0;
+        self.checkpoint_interval_secs = 0;
+        self.ms_per_slot = DEFAULT_MS_PER_SLOT;
+        self.early_bird_hour = 0;
+        self.early_bird_bonus_bps = 0;
+        self.rate_policy = RatePolicy::UseCurrent;
+        self.active_commitments = 0;
+    }
+
+    // today's program from a legacy account's data grown to SPACE: the legacy fields are
+    // kept with the rate restated in bps, and the appended zeroes get the defaults
+    pub fn from_upgraded_legacy(data: &[u8]) -> Result<Self> {
+        let legacy = LegacyFocusProgram::deserialize(&mut &data[8..])?;
+        let mut program = Self::try_deserialize(&mut &data[..])?;
+        program.reward_rate_bps = legacy.reward_rate_bps();
+        program.apply_default_config();
+        Ok(program)
+    }
+
+    // the base rate, or the promotional rate while a boost window is open
+    pub fn current_reward_rate_bps(&self, current_timestamp: i64) -> u64 {
+        if current_timestamp < self.boost_expiry {
+            self.boosted_rate_bps
+        } else {
+            self.reward_rate_bps
+        }
+    }
+    pub fn can_stake(&self, amount: u64) -> bool {
+        match self.total_staked.checked_add(amount) {
+            Some(total) => self.max_total_staked == 0 || total <= self.max_total_staked,
+            None => false,
+        }
+    }
+
+    pub fn is_authorized_caller(&self, program_id: &Pubkey) -> bool {
+        self.authorized_callers[..self.authorized_caller_count as usize].contains(program_id)
+    }
+
+    // blends the oracle's completion rate into the commitment's own. strictly additive: a
+    // missing, stale-oracle or lower attestation never reduces the success rate
+    pub fn oracle_weighted_success_bps(&self, success_bps: u64, attestation: Option<&HabitAttestation>) -> u64 {
+        match attestation {
+            Some(attestation) if self.habit_oracle != Pubkey::default() && attestation.oracle == self.habit_oracle => {
+                let blended = (success_bps * (BPS_DENOMINATOR - self.oracle_weight_bps)
+                    + attestation.completion_bps() * self.oracle_weight_bps)
+                    / BPS_DENOMINATOR;
+                success_bps.max(blended)
+            }
+            _ => success_bps,
+        }
+    }
+
+    // max_early_penalty_bps of the stake, scaled by the share of the term left
+    pub fn early_cancel_penalty(&self, amount_staked: u64, days_remaining: u64, total_days: u8) -> u64 {
+        (amount_staked as u128 * self.max_early_penalty_bps as u128 * days_remaining as u128
+            / (BPS_DENOMINATOR as u128 * total_days as u128)) as u64
+    }
+
+    // bonus tokens that may still be minted in `epoch`; the count starts over each epoch
+    pub fn epoch_mint_remaining(&self, epoch: u64) -> u64 {
+        if epoch != self.mint_epoch {
+            self.mint_cap_per_epoch
+        } else {
+            self.mint_cap_per_epoch.saturating_sub(self.epoch_minted)
+        }
+    }
+
+    // where a claim's returned stake comes from; below the failure threshold it's a partial refund
+    pub fn refund_source(&self, partial_refund: bool) -> FundingSource {
+        if partial_refund {
+            self.partial_refund_source
+        } else {
+            self.principal_source
+        }
+    }
+
+    // the routing claim_all_rewards supports: stake from the vaults, bonuses from the pool
+    pub fn has_default_funding(&self) -> bool {
+        self.bonus_source == FundingSource::RewardPool
+            && self.principal_source == FundingSource::Vault
+            && self.partial_refund_source == FundingSource::Vault
+    }
+
+    // counts a minted bonus toward `epoch`'s cap, starting the count over in a new epoch
+    pub fn record_minted(&mut self, epoch: u64, amount: u64) {
+        if epoch != self.mint_epoch {
+            self.mint_epoch = epoch;
+            self.epoch_minted = 0;
+        }
+        self.epoch_minted = self.epoch_minted.checked_add(amount).unwrap();
+    }
+
+    // min_active_days, capped at the term length so every commitment stays refundable
+    pub fn required_active_days(&self, total_days: u8) -> u8 {
+        self.min_active_days.min(total_days)
+    }
+
+    // the bonus clamped to bonus_cap_ratio_bps of the stake when a cap is set
+    pub fn capped_bonus(&self, bonus: u64, amount_staked: u64) -> u64 {
+        if self.bonus_cap_ratio_bps == 0 {
+            return bonus;
+        }
+        let cap = (amount_staked as u128 * self.bonus_cap_ratio_bps as u128 / BPS_DENOMINATOR as u128)
+            .min(u64::MAX as u128) as u64;
+        bonus.min(cap)
+    }
+
+    // whether `amount` backs every one of `total_days` with at least min_stake_per_day
+    pub fn stake_covers_days(&self, amount: u64, total_days: u8) -> bool {
+        self.min_stake_per_day
+            .checked_mul(total_days as u64)
+            .is_some_and(|required| amount >= required)
+    }
+
+    // min_stake_tokens in raw units of a mint with `mint_decimals`
+    pub fn min_stake_amount(&self, mint_decimals: u8) -> u64 {
+        10u64
+            .checked_pow(mint_decimals as u32)
+            .and_then(|unit| self.min_stake_tokens.checked_mul(unit))
+            .unwrap_or(u64::MAX)
+    }
+
+    // the bonus, rounded down to bonus_precision places of a mint with `mint_decimals`
+    // when rounding is on; whatever is cut off stays in the reward pool
+    pub fn rounded_bonus(&self, bonus: u64, mint_decimals: u8) -> u64 {
+        if !self.round_bonus {
+            return bonus;
+        }
+        10u64
+            .checked_pow(mint_decimals.saturating_sub(self.bonus_precision) as u32)
+            .map_or(0, |step| bonus - bonus % step)
+    }
+
+    // escalates with the streak: each perfect day in a row adds perfect_streak_bonus_bps of the stake
+    pub fn perfect_streak_bonus(&self, amount_staked: u64, best_perfect_day_streak: u8) -> u64 {
+        (amount_staked as u128 * self.perfect_streak_bonus_bps as u128 * best_perfect_day_streak as u128
+            / BPS_DENOMINATOR as u128) as u64
+    }
+
+    // early_bird_bonus_bps of the stake, scaled by the share of expected sessions completed early
+    pub fn early_bird_bonus(&self, amount_staked: u64, early_sessions: u16, expected_sessions: u16) -> u64 {
+        if expected_sessions == 0 {
+            return 0;
+        }
+        (amount_staked as u128 * self.early_bird_bonus_bps as u128 * early_sessions.min(expected_sessions) as u128
+            / (BPS_DENOMINATOR as u128 * expected_sessions as u128)) as u64
+    }
+
+    pub fn has_reward_curve(&self) -> bool {
+        self.curve_max_bonus_bps > 0
+    }
+
+    // the continuous bonus for a term at `success_bps`: 0 at `floor_bps`, rising linearly to
+    // curve_max_bonus_bps of the stake at the ceiling and flat above it, over-completion included
+    pub fn curve_bonus(&self, amount_staked: u64, success_bps: u64, floor_bps: u64) -> u64 {
+        if success_bps <= floor_bps {
+            return 0;
+        }
+        let max_bonus = amount_staked as u128 * self.curve_max_bonus_bps as u128;
+        let bonus = if success_bps >= self.curve_ceiling_bps {
+            max_bonus / BPS_DENOMINATOR as u128
+        } else {
+            max_bonus * (success_bps - floor_bps) as u128
+                / ((self.curve_ceiling_bps - floor_bps) as u128 * BPS_DENOMINATOR as u128)
+        };
+        bonus.min(u64::MAX as u128) as u64
+    }
+
+    // whether a commitment term from `start` to `deadline` ran entirely inside the challenge window
+    pub fn is_challenge_term(&self, start: i64, deadline: i64) -> bool {
+        self.challenge_bonus_bps > 0 && start >= self.challenge_start && deadline <= self.challenge_end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_programs_upgrade_with_the_rate_in_bps() {
+        let legacy = LegacyFocusProgram {
+            authority: Pubkey::new_unique(),
+            bump: 254,
+            total_users: 3,
+            total_staked: 5_000,
+            reward_rate: 12,
+            focus_token_mint: Pubkey::new_unique(),
+        };
+        let mut data = FocusProgram::DISCRIMINATOR.to_vec();
+        legacy.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), LegacyFocusProgram::SPACE);
+        // realloc zero-fills the grown account
+        data.resize(FocusProgram::SPACE, 0);
+
+        let program = FocusProgram::from_upgraded_legacy(&data).unwrap();
+        assert_eq!(program.authority, legacy.authority);
+        assert_eq!(program.bump, 254);
+        assert_eq!(program.total_users, 3);
+        assert_eq!(program.total_staked, 5_000);
+        assert_eq!(program.reward_rate_bps, 1_200);
+        assert_eq!(program.focus_token_mint, legacy.focus_token_mint);
+        assert_eq!(program.max_early_penalty_bps, DEFAULT_MAX_EARLY_PENALTY_BPS);
+        assert_eq!(program.ms_per_slot, DEFAULT_MS_PER_SLOT);
+    }
+}
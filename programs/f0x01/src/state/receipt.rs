@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+// proof that a user already took the one-time first session reward. it is keyed by
+// wallet rather than profile so recreating a profile can't claim it again
+#[account]
+pub struct FirstSessionReceipt {
+    pub user: Pubkey, //32
+    pub bump: u8, //1
+    pub amount: u64, //8
+    pub claimed_at: i64, //8
+}
+
+impl FirstSessionReceipt {
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8;
+}
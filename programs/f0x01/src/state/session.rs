@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
 
 #[account]
 pub struct SessionRecord {
@@ -10,8 +11,26 @@ pub struct SessionRecord {
     pub completed: bool, //1
     pub verification_slot: u64,  // 8 (slot for verification)
     pub end_timestamp: i64, //8
+    pub events_root: [u8; 32], // merkle root of the app's focus ticks, set by verify_session_events (32)
+    pub verified_ticks: u32, // ticks proven against events_root (4)
+    pub platform: u8, // PLATFORM_* tag the session was completed from, 0 = untagged (1)
+    pub early_bird: bool, // completed before the program's early_bird_hour, local time (1)
+    pub rent_payer: Pubkey, // paid the record's rent, refunded it when complete_session closes the record (32)
 }
 
 impl SessionRecord {
-    pub const SPACE: usize = 8 + 32 + 32 + 1 + 8 + 8 + 1 + 8 + 8;
+    pub const SPACE: usize = 8 + 32 + 32 + 1 + 8 + 8 + 1 + 8 + 8 + 32 + 4 + 1 + 1 + 32;
+
+    // slots that must pass after verification_slot before a full session can complete, which
+    // is shortened by the same tolerance as the duration check
+    pub fn required_slots(duration_tolerance_secs: i64, ms_per_slot: u64) -> u64 {
+        Self::required_slots_for(SESSION_DURATION_SECS - duration_tolerance_secs, ms_per_slot)
+    }
+
+    // slots that must pass for a session credited for `duration_secs` on a cluster producing
+    // a slot every `ms_per_slot`
+    pub fn required_slots_for(duration_secs: i64, ms_per_slot: u64) -> u64 {
+        let expected_slots = (duration_secs as u64).saturating_mul(1000) / ms_per_slot; // approx slots in the duration
+        expected_slots.saturating_sub(10) // allow small tolerance
+    }
 }
\ No newline at end of file
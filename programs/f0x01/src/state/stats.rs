@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+
+// program-lifetime payout totals for treasury dashboards. u128 so the sums can't
+// overflow however many claims the program sees
+#[account]
+pub struct GlobalStats {
+    pub bump: u8, //1
+    pub total_rewards_paid: u128, // everything claim_rewards paid out, principal and bonus (16)
+    pub total_bonus_paid: u128, // the bonus portion, paid from the reward pool (16)
+    pub period: u64, // index of the live period, incremented by rollover_period_stats (8)
+    pub period_started_at: i64, // when the live period started (8)
+    pub period_rewards_paid: u128, // total_rewards_paid within the live period (16)
+    pub period_bonus_paid: u128, // total_bonus_paid within the live period (16)
+    pub platform_sessions: [u64; PLATFORM_COUNT], // completed sessions per PLATFORM_* tag, at index tag - 1 (8 * 4)
+    pub terms_claimed: u64, // terms ended by a claim, an auto-renewal or a revival (8)
+    pub total_success_bps: u128, // sum of those terms' success rates, each capped at 100% (16)
+}
+
+impl GlobalStats {
+    pub const SPACE: usize = 8 + 1 + 16 + 16 + 8 + 8 + 16 + 16 + 8 * PLATFORM_COUNT + 8 + 16;
+
+    // principal returned is total_rewards_paid - total_bonus_paid
+    pub fn record_claim(&mut self, principal: u64, bonus: u64) {
+        self.total_rewards_paid = self.total_rewards_paid
+            .checked_add(principal as u128 + bonus as u128)
+            .unwrap();
+        self.total_bonus_paid = self.total_bonus_paid.checked_add(bonus as u128).unwrap();
+        self.period_rewards_paid = self.period_rewards_paid
+            .checked_add(principal as u128 + bonus as u128)
+            .unwrap();
+        self.period_bonus_paid = self.period_bonus_paid.checked_add(bonus as u128).unwrap();
+    }
+
+    pub fn record_term(&mut self, success_bps: u64) {
+        self.terms_claimed = self.terms_claimed.checked_add(1).unwrap();
+        self.total_success_bps = self.total_success_bps
+            .checked_add(success_bps.min(BPS_DENOMINATOR) as u128)
+            .unwrap();
+    }
+
+    // mean success rate of every ended term, None before the first
+    pub fn average_success_bps(&self) -> Option<u64> {
+        if self.terms_claimed == 0 {
+            return None;
+        }
+        Some((self.total_success_bps / self.terms_claimed as u128) as u64)
+    }
+
+    // `platform` is a validated PLATFORM_* tag
+    pub fn record_platform_session(&mut self, platform: u8) {
+        let index = platform as usize - 1;
+        self.platform_sessions[index] = self.platform_sessions[index].checked_add(1).unwrap();
+    }
+}
+
+// one finished period's payout totals, archived by rollover_period_stats
+#[account]
+pub struct PeriodStats {
+    pub bump: u8, //1
+    pub period: u64, //8
+    pub started_at: i64, //8
+    pub ended_at: i64, //8
+    pub rewards_paid: u128, //16
+    pub bonus_paid: u128, //16
+}
+
+impl PeriodStats {
+    pub const SPACE: usize = 8 + 1 + 8 + 8 + 8 + 16 + 16;
+}
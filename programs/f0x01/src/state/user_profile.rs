@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::*;
+use crate::state::WeeklyLeagueEntry;
+use crate::utils::day_floor;
 
 #[account]
 pub struct UserProfile {
@@ -9,8 +13,183 @@ pub struct UserProfile {
     pub current_streak: u16, //2
     pub best_streak: u16, //2
     pub last_active_day: i64,  // unix timestamp of last active day (8)
+    pub reminder_hour: u8, // UTC hour (0-23) off-chain notifiers should remind at (1)
+    pub reminders_enabled: bool, //1
+    pub rewards_auto_staked: u64, // portion of total_rewards_earned already restaked on milestones (8)
+    pub referrer: Pubkey, // who referred this user, default = none (32)
+    pub qualified_referrals: u32, // referees who completed a commitment at the bonus tier (4)
+    pub claimed_airdrop_tiers: u8, // bit i set once streak airdrop tier i has been paid (1)
+    pub weekly_sessions: u32, // sessions completed since the league counter was last reset (4)
+    pub league_week: i64, // week index weekly_sessions is counted for (8)
+    pub longest_session_secs: u64, // personal best completed session duration (8)
+    pub reputation: u32, // score other programs can read, see refresh_reputation (4)
+    pub total_expected_sessions_all_time: u64, // sessions committed to across every commitment term (8)
+    pub last_commitment_end: i64, // when the user last claimed a commitment, 0 = none since the last bridged gap (8)
+    pub last_claim_at: i64, // when the user last claimed a bonus, for the program's claim cooldown (8)
+    pub daily_minutes_goal: u16, // personal focus minutes per day, independent of sessions, 0 = no goal (2)
+    pub focus_secs_today: u32, // completed focus time on focus_day, in seconds so short sessions add up (4)
+    pub focus_day: i64, // start of the UTC day focus_secs_today is counted for (8)
+    pub referral_qualified: bool, // already counted in the referrer's qualified_referrals (1)
 }
 
 impl UserProfile {
-    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 2 + 2 + 8;
-}
\ No newline at end of file
+    pub const SPACE: usize = 8 + 32 + 1 + 8 + 8 + 2 + 2 + 8 + 1 + 1 + 8 + 32 + 4 + 1 + 4 + 8 + 8 + 4 + 8 + 8 + 8 + 2 + 4 + 8 + 1;
+
+    // extends or restarts the streak for the current day, at most once per day so a session
+    // counted on start isn't counted again on completion. returns true on a streak milestone
+    pub fn record_active_day(&mut self, current_timestamp: i64) -> bool {
+        let today_timestamp = day_floor(current_timestamp);
+        let last_active_day_timestamp = day_floor(self.last_active_day);
+
+        let mut hit_milestone = false;
+        if today_timestamp > last_active_day_timestamp {
+            // check if this is consecutive day (yesterday)
+            if today_timestamp - last_active_day_timestamp <= SECONDS_PER_DAY {
+                self.current_streak += 1;
+                if self.current_streak > self.best_streak {
+                    self.best_streak = self.current_streak;
+                }
+                hit_milestone = self.current_streak.checked_rem(STREAK_MILESTONE_DAYS) == Some(0);
+            } else {
+                // streak broken
+                self.current_streak = 1;
+            }
+            self.last_active_day = today_timestamp;
+        }
+
+        hit_milestone
+    }
+
+    // a new commitment started within `grace_days` of the last claim carries the streak over
+    // the gap, provided the streak was still going when that commitment was claimed. each
+    // claim bridges at most one gap
+    pub fn bridge_commitment_gap(&mut self, current_timestamp: i64, grace_days: u8) {
+        if grace_days == 0 || self.last_commitment_end == 0 {
+            return;
+        }
+        let gap_start = day_floor(self.last_commitment_end);
+        let today_timestamp = day_floor(current_timestamp);
+        self.last_commitment_end = 0;
+        if today_timestamp - gap_start > grace_days as i64 * SECONDS_PER_DAY
+            || gap_start - day_floor(self.last_active_day) > SECONDS_PER_DAY
+        {
+            return;
+        }
+
+        // yesterday counts as active, so a session today extends the streak
+        self.last_active_day = self.last_active_day.max(today_timestamp - SECONDS_PER_DAY);
+    }
+
+    // whether the program's claim cooldown has passed since the user's last bonus claim
+    pub fn can_claim_bonus(&self, claim_cooldown_secs: i64, current_timestamp: i64) -> bool {
+        self.last_claim_at == 0 || current_timestamp - self.last_claim_at >= claim_cooldown_secs
+    }
+
+    // adds a completed session's focus time to the UTC day it started on, the same day the
+    // streak counts it for. a session credited late for an earlier day no longer counts
+    pub fn record_focus_secs(&mut self, start_timestamp: i64, secs: u64) {
+        let session_day = day_floor(start_timestamp);
+        if session_day > self.focus_day {
+            self.focus_day = session_day;
+            self.focus_secs_today = 0;
+        }
+        if session_day == self.focus_day {
+            self.focus_secs_today = self.focus_secs_today.saturating_add(secs.min(u32::MAX as u64) as u32);
+        }
+    }
+
+    // counts a full session toward the league week it started in. a later week can only
+    // take over once reset_weekly_stats archived this one, so its sessions can't inflate the
+    // archived entry; a week with nothing to archive just moves on. a session credited late
+    // for an already archived week isn't counted
+    pub fn record_league_session(&mut self, start_timestamp: i64) -> Result<()> {
+        let session_week = WeeklyLeagueEntry::week_of(start_timestamp);
+        if session_week > self.league_week {
+            require!(self.weekly_sessions == 0, FocusError::LeagueWeekNotArchived);
+            self.league_week = session_week;
+        }
+        if session_week == self.league_week {
+            self.weekly_sessions += 1;
+        }
+        Ok(())
+    }
+
+    pub fn daily_goal_met(&self, current_timestamp: i64) -> bool {
+        self.daily_minutes_goal > 0
+            && self.focus_day == day_floor(current_timestamp)
+            && self.focus_secs_today as u64 >= self.daily_minutes_goal as u64 * 60
+    }
+
+    // true the first time this referee qualifies, so the referrer counts each referee once
+    pub fn qualify_referral(&mut self) -> bool {
+        let first = !self.referral_qualified;
+        self.referral_qualified = true;
+        first
+    }
+
+    // counts a new commitment term's expected sessions toward the all-time completion rate
+    pub fn record_commitment(&mut self, expected_sessions: u16) {
+        self.total_expected_sessions_all_time = self.total_expected_sessions_all_time
+            .checked_add(expected_sessions as u64)
+            .unwrap();
+    }
+
+    // swaps a commitment's counted expected sessions for its new count after it changed
+    pub fn restate_commitment(&mut self, previous_expected: u16, expected_sessions: u16) {
+        self.total_expected_sessions_all_time = self.total_expected_sessions_all_time
+            .saturating_sub(previous_expected as u64)
+            .checked_add(expected_sessions as u64)
+            .unwrap();
+    }
+
+    // completed sessions as a share of all sessions committed to, in bps. 0 before the
+    // first commitment, and at most 100% since bonus sessions also count as completed
+    pub fn all_time_completion_bps(&self) -> u64 {
+        if self.total_expected_sessions_all_time == 0 {
+            return 0;
+        }
+        (self.total_sessions_completed.saturating_mul(BPS_DENOMINATOR) / self.total_expected_sessions_all_time)
+            .min(BPS_DENOMINATOR)
+    }
+
+    // recomputes reputation from the lifetime stats; the score is a pure function of
+    // them, so any reader can check it
+    pub fn refresh_reputation(&mut self) {
+        let score = self.total_sessions_completed.saturating_mul(REPUTATION_PER_SESSION)
+            .saturating_add((self.best_streak as u64).saturating_mul(REPUTATION_PER_STREAK_DAY))
+            .saturating_add(self.total_rewards_earned / REPUTATION_REWARD_UNIT);
+        self.reputation = score.min(u32::MAX as u64) as u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn league_sessions_wait_for_the_previous_week_to_be_archived() {
+        let mut user_profile = UserProfile::try_deserialize_unchecked(&mut &[0u8; UserProfile::SPACE][..]).unwrap();
+        let week = 2_000;
+        user_profile.league_week = week;
+        let start = week * SECONDS_PER_WEEK;
+
+        assert!(user_profile.record_league_session(start).is_ok());
+        assert_eq!(user_profile.weekly_sessions, 1);
+        // the next week's session can't land in this week's count
+        assert!(user_profile.record_league_session(start + SECONDS_PER_WEEK).is_err());
+        assert_eq!((user_profile.league_week, user_profile.weekly_sessions), (week, 1));
+
+        // once archived, as reset_weekly_stats leaves it, the next week counts
+        user_profile.weekly_sessions = 0;
+        user_profile.league_week = week + 1;
+        assert!(user_profile.record_league_session(start + SECONDS_PER_WEEK).is_ok());
+        // a late credit for the archived week isn't counted
+        assert!(user_profile.record_league_session(start).is_ok());
+        assert_eq!(user_profile.weekly_sessions, 1);
+
+        // an empty week has nothing to archive and moves straight on
+        user_profile.weekly_sessions = 0;
+        assert!(user_profile.record_league_session(start + 3 * SECONDS_PER_WEEK).is_ok());
+        assert_eq!((user_profile.league_week, user_profile.weekly_sessions), (week + 3, 1));
+    }
+}
@@ -1,8 +1,237 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount};
+use crate::constants::*;
+use crate::error::*;
+use crate::state::RatePolicy;
 
 // utility functions that might be needed across instructions
+
+// start of the UTC day containing `timestamp`. euclidean division floors pre-epoch
+// timestamps to the day they fall in, where `/` would round them up toward zero
+pub fn day_floor(timestamp: i64) -> i64 {
+    timestamp.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY
+}
+
 pub fn get_current_day_timestamp() -> Result<i64> {
-    let timestamp = Clock::get()?.unix_timestamp;
-    let day_in_seconds = 86400;
-    Ok((timestamp / day_in_seconds) * day_in_seconds)
-}
\ No newline at end of file
+    Ok(day_floor(Clock::get()?.unix_timestamp))
+}
+
+// for instructions that take a start time from the client rather than the clock: it must
+// lie in [current_timestamp - max_backdate_secs, current_timestamp]
+pub fn check_backdated_timestamp(timestamp: i64, current_timestamp: i64, max_backdate_secs: i64) -> Result<()> {
+    require!(timestamp <= current_timestamp, FocusError::ImplausibleTimeline);
+    require!(current_timestamp - timestamp <= max_backdate_secs, FocusError::BackdateTooFar);
+    Ok(())
+}
+
+// checks a claim's vault from its owner and data: a vault that was closed, isn't a token
+// account or holds less than `vault_held` (the stake plus any gifted tokens) is missing
+pub fn check_vault_funding(owner: &Pubkey, data: &[u8], vault_held: u64) -> Result<()> {
+    require!(owner == &token::ID && !data.is_empty(), FocusError::VaultMissing);
+    let vault = TokenAccount::try_deserialize(&mut &data[..])?;
+    require!(vault.amount >= vault_held, FocusError::VaultMissing);
+    Ok(())
+}
+
+// a claim's transfers must all be covered before the first one runs: check_vault_funding
+// covers the vault, this the pool for whatever the claim pays out of it
+pub fn check_claim_funding(pool_balance: Option<u64>, pool_payout: u64) -> Result<()> {
+    if pool_payout > 0 {
+        let pool_balance = pool_balance.ok_or(FocusError::RewardPoolRequired)?;
+        require!(pool_balance >= pool_payout, FocusError::InsufficientBalance);
+    }
+    Ok(())
+}
+
+// checks a session's slot timing, from `verification_slot` to `current_slot`. a record
+// without a verification slot (e.g. one written by a migration) would pass any slot check
+// against slot 0, so it is left to the timestamp checks
+pub fn check_session_slots(
+    verification_slot: u64,
+    creation_slot: u64,
+    current_slot: u64,
+    required_slots: u64,
+    elapsed_secs: i64,
+    ms_per_slot: u64,
+    max_clock_drift_bps: u64,
+) -> Result<()> {
+    if verification_slot == 0 {
+        return Ok(());
+    }
+    // a session started before its commitment existed means a cloned or replayed account
+    require!(verification_slot >= creation_slot, FocusError::ImplausibleTimeline);
+
+    let slot_difference = current_slot.saturating_sub(verification_slot);
+    require!(slot_difference >= required_slots, FocusError::SlotVerificationFailed);
+
+    // cross-check the wall-clock duration against slot time to catch a skewed clock
+    if max_clock_drift_bps > 0 {
+        let elapsed_secs = elapsed_secs as u64;
+        let slot_secs = slot_difference.saturating_mul(ms_per_slot) / 1000;
+        let drift_secs = elapsed_secs.abs_diff(slot_secs);
+        require!(
+            drift_secs.saturating_mul(BPS_DENOMINATOR) <= elapsed_secs.saturating_mul(max_clock_drift_bps),
+            FocusError::ClockDriftDetected
+        );
+    }
+    Ok(())
+}
+
+// the perfect-day streak after commitment day `day` was perfect, given the streak that
+// ended on `last_perfect_day`
+pub fn extend_perfect_day_streak(streak: u8, last_perfect_day: u8, day: u8) -> u8 {
+    if streak > 0 && last_perfect_day.checked_add(1) == Some(day) {
+        streak.saturating_add(1)
+    } else {
+        1
+    }
+}
+
+// the rate one tuning step moves `rate_bps` to: up by `step_bps` when the average completion
+// is low, down by it when completion is high, and always inside [min_rate_bps, max_rate_bps]
+pub fn tuned_reward_rate(rate_bps: u64, average_success_bps: u64, min_rate_bps: u64, max_rate_bps: u64, step_bps: u64) -> u64 {
+    let rate = if average_success_bps < TUNE_LOW_COMPLETION_BPS {
+        rate_bps.saturating_add(step_bps)
+    } else if average_success_bps > TUNE_HIGH_COMPLETION_BPS {
+        rate_bps.saturating_sub(step_bps)
+    } else {
+        rate_bps
+    };
+    rate.clamp(min_rate_bps, max_rate_bps)
+}
+
+// the rate a claim uses under `policy`, given the rate at creation and the current one
+pub fn policy_reward_rate(policy: RatePolicy, locked_rate_bps: u64, current_rate_bps: u64) -> u64 {
+    match policy {
+        RatePolicy::LockAtCreation => locked_rate_bps,
+        RatePolicy::UseCurrent => current_rate_bps,
+        RatePolicy::BetterOfBoth => locked_rate_bps.max(current_rate_bps),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_floor_handles_negative_timestamps() {
+        assert_eq!(day_floor(0), 0);
+        assert_eq!(day_floor(SECONDS_PER_DAY + 5), SECONDS_PER_DAY);
+        // one second before the epoch belongs to the day before it
+        assert_eq!(day_floor(-1), -SECONDS_PER_DAY);
+        assert_eq!(day_floor(-SECONDS_PER_DAY), -SECONDS_PER_DAY);
+        assert_eq!(day_floor(-SECONDS_PER_DAY - 1), -2 * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn backdated_timestamps_stay_within_the_limit() {
+        let now = 10 * SECONDS_PER_DAY;
+        assert!(check_backdated_timestamp(now, now, 0).is_ok());
+        assert!(check_backdated_timestamp(now - 60, now, 60).is_ok());
+        assert!(check_backdated_timestamp(now - 61, now, 60).is_err());
+        assert!(check_backdated_timestamp(now + 1, now, 60).is_err());
+    }
+
+    #[test]
+    fn underfunded_claims_fail_before_any_transfer() {
+        assert!(check_claim_funding(Some(100), 100).is_ok());
+        // a pool that can't cover the bonus, or wasn't passed
+        assert!(check_claim_funding(Some(99), 100).is_err());
+        assert!(check_claim_funding(None, 100).is_err());
+        // nothing is paid from the pool, so it isn't needed
+        assert!(check_claim_funding(None, 0).is_ok());
+    }
+
+    #[test]
+    fn vaults_must_be_open_token_accounts_holding_the_stake_and_gifts() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token::spl_token::state::{Account, AccountState};
+
+        let mut data = vec![0u8; Account::LEN];
+        let vault = Account { amount: 1_000, state: AccountState::Initialized, ..Account::default() };
+        Account::pack(vault, &mut data).unwrap();
+        assert!(check_vault_funding(&token::ID, &data, 1_000).is_ok());
+        // drained below the stake and gifts it holds
+        assert!(check_vault_funding(&token::ID, &data, 1_001).is_err());
+        // closed, or reassigned away from the token program
+        assert!(check_vault_funding(&token::ID, &[], 0).is_err());
+        assert!(check_vault_funding(&crate::ID, &data, 0).is_err());
+    }
+
+    #[test]
+    fn sessions_without_a_verification_slot_are_verified_by_time_only() {
+        // 100 slots of 400ms for a 40s session
+        assert!(check_session_slots(1_100, 1_000, 1_200, 100, 40, 400, 500).is_ok());
+        assert!(check_session_slots(1_100, 1_000, 1_150, 100, 40, 400, 500).is_err());
+        // started before the commitment was created
+        assert!(check_session_slots(900, 1_000, 1_200, 100, 40, 400, 500).is_err());
+        // a clock running twice as fast as the slots
+        assert!(check_session_slots(1_100, 1_000, 1_200, 100, 80, 400, 500).is_err());
+        // slot 0 skips every slot check, even with too few slots and a skewed clock
+        assert!(check_session_slots(0, 1_000, 50, 100, 80, 400, 500).is_ok());
+    }
+
+    #[test]
+    fn perfect_days_build_a_streak_until_one_is_missed() {
+        let (mut streak, mut last) = (0, 0);
+        for day in 0..5 {
+            streak = extend_perfect_day_streak(streak, last, day);
+            last = day;
+        }
+        assert_eq!(streak, 5);
+        // day 5 wasn't perfect, so day 6 starts over
+        assert_eq!(extend_perfect_day_streak(streak, last, 6), 1);
+        // the same day can't count twice
+        assert_eq!(extend_perfect_day_streak(streak, last, last), 1);
+    }
+
+    #[test]
+    fn required_slots_follow_the_configured_slot_time() {
+        use crate::state::SessionRecord;
+
+        // a full session's worth of slots at the cluster's actual slot time meets the requirement
+        for ms_per_slot in [400, 800] {
+            let produced_slots = SESSION_DURATION_SECS as u64 * 1000 / ms_per_slot;
+            assert!(produced_slots >= SessionRecord::required_slots(0, ms_per_slot));
+        }
+        assert_eq!(SessionRecord::required_slots(0, 400), 8240);
+        assert_eq!(SessionRecord::required_slots(0, 800), 4115);
+        // assuming mainnet's 400ms on an 800ms cluster would demand twice the slots that pass
+        assert!(SessionRecord::required_slots(0, 400) > SESSION_DURATION_SECS as u64 * 1000 / 800);
+    }
+
+    #[test]
+    fn tolerated_sessions_pass_the_slot_check() {
+        use crate::state::SessionRecord;
+
+        // a session completed as soon as the tolerance allows has only produced the slots of
+        // the shortened duration, which must be enough
+        for tolerance_secs in [0, 5, 30, MAX_DURATION_TOLERANCE_SECS] {
+            let produced_slots = (SESSION_DURATION_SECS - tolerance_secs) as u64 * 1000 / 400;
+            assert!(produced_slots >= SessionRecord::required_slots(tolerance_secs, 400));
+        }
+        assert_eq!(SessionRecord::required_slots(MAX_DURATION_TOLERANCE_SECS, 400), 8090);
+    }
+
+    #[test]
+    fn low_completion_raises_the_rate_within_bounds() {
+        assert_eq!(tuned_reward_rate(1000, 4000, 500, 1500, 200), 1200);
+        // the step stops at the upper bound
+        assert_eq!(tuned_reward_rate(1400, 4000, 500, 1500, 200), 1500);
+        assert_eq!(tuned_reward_rate(1000, 9500, 500, 1500, 200), 800);
+        assert_eq!(tuned_reward_rate(600, 9500, 500, 1500, 200), 500);
+        assert_eq!(tuned_reward_rate(1000, 7500, 500, 1500, 200), 1000);
+    }
+
+    #[test]
+    fn rate_policy_picks_the_claim_rate() {
+        // the rate was raised after creation
+        assert_eq!(policy_reward_rate(RatePolicy::LockAtCreation, 1000, 1500), 1000);
+        assert_eq!(policy_reward_rate(RatePolicy::UseCurrent, 1000, 1500), 1500);
+        assert_eq!(policy_reward_rate(RatePolicy::BetterOfBoth, 1000, 1500), 1500);
+        // the rate was cut after creation
+        assert_eq!(policy_reward_rate(RatePolicy::LockAtCreation, 1000, 500), 1000);
+        assert_eq!(policy_reward_rate(RatePolicy::UseCurrent, 1000, 500), 500);
+        assert_eq!(policy_reward_rate(RatePolicy::BetterOfBoth, 1000, 500), 1000);
+    }
+}
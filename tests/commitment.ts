@@ -10,7 +10,8 @@ import {
   createMint,
   createAssociatedTokenAccount,
   mintTo,
-  getAssociatedTokenAddress
+  getAssociatedTokenAddress,
+  getMint
 } from "@solana/spl-token";
 import { assert, expect } from "chai";
 import { F0x01 } from "../target/types/f0x01";
@@ -114,6 +115,7 @@ describe("F0x01 Commitment Tests", () => {
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          instructionsSysvar: null,
         })
         .signers([user])
         .rpc({ commitment: 'confirmed' });
@@ -202,8 +204,8 @@ describe("F0x01 Commitment Tests", () => {
       }
 
       // OPTIMIZATION: Mint fewer tokens, just enough for tests
-      // Mint 50 tokens (with 6 decimals) instead of 1000
-      const tokensNeeded = 50_000_000;
+      // Mint 150 tokens (with 6 decimals) instead of 1000
+      const tokensNeeded = 150_000_000;
       await mintTo(
         provider.connection,
         wallet.payer,
@@ -323,6 +325,7 @@ describe("F0x01 Commitment Tests", () => {
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          instructionsSysvar: null,
         })
         .signers([userKeypair])
         .rpc({ commitment: 'confirmed' });
@@ -359,6 +362,7 @@ describe("F0x01 Commitment Tests", () => {
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          instructionsSysvar: null,
         })
         .signers([userKeypair])
         .rpc({ commitment: 'confirmed' });
@@ -396,8 +400,11 @@ describe("F0x01 Commitment Tests", () => {
     
     // Get the program details
     const programData = await program.account.focusProgram.fetch(focusProgramPda);
-    const rewardRate = programData.rewardRate.toNumber();
+    const rewardRateBps = programData.rewardRateBps.toNumber();
     await sleep(200);
+
+    // a zero rate is rejected at initialization, so 90%+ completers always earn a bonus
+    expect(rewardRateBps).to.be.greaterThan(0);
     
     // Total expected sessions
     const totalExpectedSessions = commitment.sessionsPerDay * commitment.totalDays;
@@ -431,7 +438,7 @@ describe("F0x01 Commitment Tests", () => {
       if (successRate >= 0.9) {
         // Full stake back plus bonus
         const baseStake = commitment.amountStaked.toNumber();
-        const bonus = (baseStake * rewardRate) / 100;
+        const bonus = Math.floor((baseStake * rewardRateBps) / 10000);
         rewardAmount = baseStake + bonus;
       } else if (successRate >= 0.75) {
         // Return original stake only
@@ -443,6 +450,1820 @@ describe("F0x01 Commitment Tests", () => {
       
       // Just verify calculation works, no need to check values
       expect(rewardAmount).to.be.a('number');
+      if (successRate >= 0.9) {
+        expect(rewardAmount).to.be.greaterThan(commitment.amountStaked.toNumber());
+      }
+    }
+  });
+
+  // Test 6: Pause several commitments in a single instruction
+  it("Pauses all of a user's commitments at once", async () => {
+    const pauseIds = [new anchor.BN(110), new anchor.BN(111), new anchor.BN(112)];
+    const pausePdas: PublicKey[] = [];
+
+    for (const id of pauseIds) {
+      const result = await createCommitment(
+        id,
+        new anchor.BN(1_000_000),
+        1,
+        3,
+        userKeypair,
+        userProfilePda,
+        userTokenAccount
+      );
+
+      if (!result.success) {
+        console.error("Failed to create commitment for pausing:", result.error);
+        throw result.error;
+      }
+      pausePdas.push(result.commitmentPda);
+    }
+
+    await program.methods
+      .pauseAllCommitments()
+      .accountsStrict({
+        user: userKeypair.publicKey,
+      })
+      .remainingAccounts(
+        pausePdas.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+      )
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    for (const pda of pausePdas) {
+      const commitment = await program.account.focusCommitment.fetch(pda);
+      expect(commitment.isPaused).to.be.true;
+      expect(commitment.pauseCount).to.equal(1);
+      expect(commitment.pausedAt.toNumber()).to.be.greaterThan(0);
+    }
+
+    await sleep(300);
+  });
+
+  // Test 7: Opt out of keeping per-session history
+  it("Toggles session record retention on a commitment", async () => {
+    let commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.keepSessionRecords).to.be.true;
+    expect(commitment.sessionsCompleted).to.equal(0);
+    expect(commitment.totalFocusSecs.toNumber()).to.equal(0);
+
+    await program.methods
+      .setKeepSessionRecords(false)
+      .accountsStrict({
+        commitment: maxParamsCommitmentPda,
+        user: userKeypair.publicKey,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.keepSessionRecords).to.be.false;
+
+    await sleep(300);
+  });
+
+  // Test 8: Countdown view for the next allowed session
+  it("Reports when the next session may start", async () => {
+    const now = Math.floor(Date.now() / 1000);
+
+    // fresh commitment with no sessions yet is eligible immediately
+    const eligibleAt = await program.methods
+      .nextSessionEligibleAt()
+      .accountsStrict({ commitment: maxParamsCommitmentPda, focusProgram: focusProgramPda })
+      .view();
+    expect(eligibleAt.toNumber()).to.be.closeTo(now, 120);
+
+    // a paused commitment can't take sessions, so the sentinel is returned
+    const [pausedPda] = findCommitmentPdas(userKeypair.publicKey, new anchor.BN(110));
+    const pausedEligibleAt = await program.methods
+      .nextSessionEligibleAt()
+      .accountsStrict({ commitment: pausedPda, focusProgram: focusProgramPda })
+      .view();
+    expect(pausedEligibleAt.toNumber()).to.equal(-1);
+
+    // a focus window two hours ahead holds the first session back until it opens
+    const result = await createCommitment(
+      new anchor.BN(419), stakeAmount, 1, 2, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const created = await program.account.focusCommitment.fetch(result.commitmentPda);
+    const startHourBoundary = created.startTimestamp.toNumber() - (created.startTimestamp.toNumber() % 3600);
+    const windowStartHour = (new Date(startHourBoundary * 1000).getUTCHours() + 2) % 24;
+    await program.methods
+      .setFocusWindow(windowStartHour, (windowStartHour + 1) % 24)
+      .accountsStrict({
+        commitment: result.commitmentPda,
+        user: userKeypair.publicKey,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+    const windowEligibleAt = await program.methods
+      .nextSessionEligibleAt()
+      .accountsStrict({ commitment: result.commitmentPda, focusProgram: focusProgramPda })
+      .view();
+    expect(windowEligibleAt.toNumber()).to.equal(startHourBoundary + 2 * 3600);
+  });
+
+  // Test 9: Program-wide minimum commitment length
+  it("Rejects commitments shorter than the program minimum", async () => {
+    await program.methods
+      .setMinCommitmentDays(2)
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    try {
+      const result = await createCommitment(
+        new anchor.BN(113),
+        new anchor.BN(1_000_000),
+        1,
+        1, // below the 2 day minimum
+        userKeypair,
+        userProfilePda,
+        userTokenAccount
+      );
+      expect(result.success).to.be.false;
+      expect(result.error.toString()).to.include("CommitmentTooShort");
+    } finally {
+      // restore the default so other suites can create 1-day commitments
+      await program.methods
+        .setMinCommitmentDays(0)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+    }
+
+    await sleep(300);
+  });
+
+  // Test 10: Authority audit of the program-wide total_staked
+  it("Audits and corrects total_staked drift", async () => {
+    const activeCommitments = (await program.account.focusCommitment.all())
+      .filter((c) => c.account.isActive);
+    const toMeta = (c) => ({ pubkey: c.publicKey, isWritable: false, isSigner: false });
+
+    const before = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(before.activeCommitments.toNumber()).to.equal(activeCommitments.length);
+
+    // applying from a subset would shrink total_staked below what is still owed
+    const subset = activeCommitments.slice(0, 1);
+    try {
+      await program.methods
+        .auditTotalStaked(true)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .remainingAccounts(subset.map(toMeta))
+        .rpc({ commitment: 'confirmed' });
+      expect.fail("Should have rejected applying a partial audit");
+    } catch (error) {
+      expect(error.toString()).to.include("IncompleteAudit");
+    }
+
+    let programData = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programData.totalStaked.toString()).to.equal(before.totalStaked.toString());
+
+    // auditing the full set sets it to the true sum
+    const expectedTotal = activeCommitments.reduce(
+      (sum, c) => sum.add(c.account.amountStaked),
+      new anchor.BN(0)
+    );
+    await program.methods
+      .auditTotalStaked(true)
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .remainingAccounts(activeCommitments.map(toMeta))
+      .rpc({ commitment: 'confirmed' });
+
+    programData = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programData.totalStaked.toString()).to.equal(expectedTotal.toString());
+
+    await sleep(300);
+  });
+
+  // Test 11: Focus buddy registry
+  it("Registers two compatible users and matches them as partners", async () => {
+    // set up a second user with their own profile and commitment
+    const buddy = Keypair.generate();
+    const fundBuddyTx = new anchor.web3.Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: wallet.publicKey,
+        toPubkey: buddy.publicKey,
+        lamports: 50000000,
+      })
+    );
+    await provider.sendAndConfirm(fundBuddyTx);
+
+    const buddyTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      wallet.payer,
+      tokenMint,
+      buddy.publicKey,
+      { commitment: 'confirmed' }
+    );
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      tokenMint,
+      buddyTokenAccount,
+      wallet.publicKey,
+      5_000_000,
+      [],
+      { commitment: 'confirmed' }
+    );
+
+    const [buddyProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_profile"), buddy.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .createUserProfile()
+      .accountsStrict({
+        userProfile: buddyProfilePda,
+        user: buddy.publicKey,
+        focusProgram: focusProgramPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buddy])
+      .rpc({ commitment: 'confirmed' });
+
+    // both commitments target 10 sessions per day
+    const buddyResult = await createCommitment(
+      new anchor.BN(200),
+      new anchor.BN(1_000_000),
+      10,
+      30,
+      buddy,
+      buddyProfilePda,
+      buddyTokenAccount
+    );
+    if (!buddyResult.success) {
+      throw buddyResult.error;
+    }
+
+    const findListing = (user: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("partner_listing"), user.toBuffer()],
+        program.programId
+      )[0];
+    const userListingPda = findListing(userKeypair.publicKey);
+    const buddyListingPda = findListing(buddy.publicKey);
+
+    await program.methods
+      .registerForPartner(60) // UTC+1
+      .accountsStrict({
+        partnerListing: userListingPda,
+        commitment: maxParamsCommitmentPda,
+        user: userKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    await program.methods
+      .registerForPartner(120) // UTC+2
+      .accountsStrict({
+        partnerListing: buddyListingPda,
+        commitment: buddyResult.commitmentPda,
+        user: buddy.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buddy])
+      .rpc({ commitment: 'confirmed' });
+
+    await program.methods
+      .matchPartner()
+      .accountsStrict({
+        listingA: userListingPda,
+        listingB: buddyListingPda,
+        commitmentA: maxParamsCommitmentPda,
+        commitmentB: buddyResult.commitmentPda,
+        userA: userKeypair.publicKey,
+        userB: buddy.publicKey,
+        matcher: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    // commitments are linked to each other
+    const userCommitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    const buddyCommitment = await program.account.focusCommitment.fetch(buddyResult.commitmentPda);
+    expect(userCommitment.accountabilityPartner.toString()).to.equal(buddyResult.commitmentPda.toString());
+    expect(buddyCommitment.accountabilityPartner.toString()).to.equal(maxParamsCommitmentPda.toString());
+
+    // and both users have been removed from the pool
+    expect(await provider.connection.getAccountInfo(userListingPda)).to.be.null;
+    expect(await provider.connection.getAccountInfo(buddyListingPda)).to.be.null;
+
+    await sleep(300);
+  });
+
+  // Test 12: Program downtime is credited back to commitment deadlines
+  it("Shifts commitment deadlines by program downtime", async () => {
+    const before = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+
+    await program.methods
+      .setProgramPaused(true)
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    // no new commitments while the program is down
+    const pausedResult = await createCommitment(
+      new anchor.BN(114),
+      new anchor.BN(1_000_000),
+      1,
+      1,
+      userKeypair,
+      userProfilePda,
+      userTokenAccount
+    );
+    expect(pausedResult.success).to.be.false;
+    expect(pausedResult.error.toString()).to.include("ProgramPaused");
+
+    await sleep(2000);
+    await program.methods
+      .setProgramPaused(false)
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    const programData = await program.account.focusProgram.fetch(focusProgramPda);
+    const expectedCredit = programData.totalDowntimeSecs.sub(before.downtimeCreditedSecs);
+    expect(expectedCredit.toNumber()).to.be.greaterThan(0);
+
+    await program.methods
+      .grantDowntimeCredit()
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .remainingAccounts([
+        { pubkey: maxParamsCommitmentPda, isWritable: true, isSigner: false },
+      ])
+      .rpc({ commitment: 'confirmed' });
+
+    const after = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(after.startTimestamp.sub(before.startTimestamp).toString()).to.equal(expectedCredit.toString());
+    expect(after.downtimeCreditedSecs.toString()).to.equal(programData.totalDowntimeSecs.toString());
+
+    await sleep(300);
+  });
+
+  // Test 13: Reward rate is expressed in basis points
+  it("Applies a 1250 bps reward rate as a 12.5% bonus", async () => {
+    const original = (await program.account.focusProgram.fetch(focusProgramPda)).rewardRateBps;
+
+    await program.methods
+      .setRewardRate(new anchor.BN(1250))
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    try {
+      const programData = await program.account.focusProgram.fetch(focusProgramPda);
+      expect(programData.rewardRateBps.toNumber()).to.equal(1250);
+
+      // the bonus reserved at creation is the full-completion bonus the claim pays
+      const stake = new anchor.BN(1_000_000);
+      const result = await createCommitment(
+        new anchor.BN(418), stake, 1, 2, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(result.success).to.be.true;
+      const created = await program.account.focusCommitment.fetch(result.commitmentPda);
+      expect(created.lockedRewardRateBps.toNumber()).to.equal(1250);
+      expect(created.reservedBonus.toNumber()).to.equal(125_000);
+
+      // a program already in today's layout has no legacy percent to restate
+      try {
+        await program.methods
+          .upgradeLegacyProgram()
+          .accountsStrict({
+            focusProgram: focusProgramPda,
+            authority: wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc({ commitment: 'confirmed' });
+        expect.fail("Should have rejected upgrading a program already in basis points");
+      } catch (error) {
+        expect(error.toString()).to.include("ProgramNotUpgradable");
+      }
+    } finally {
+      await program.methods
+        .setRewardRate(original)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+    }
+  });
+
+  // Test 14: Promotional boost window and per-commitment rate locks
+  it("Uses the boosted rate only inside the window and not for locked commitments", async function() {
+    this.timeout(60000);
+
+    const programData = await program.account.focusProgram.fetch(focusProgramPda);
+    const baseRate = programData.rewardRateBps;
+    const boostedRate = baseRate.muln(2);
+
+    // lock the simulation commitment to the rate it was created with
+    await program.methods
+      .lockRewardRate()
+      .accountsStrict({
+        commitment: simulationCommitmentPda,
+        user: userKeypair.publicKey,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    const until = Math.floor(Date.now() / 1000) + 8;
+    await program.methods
+      .boostRewardRate(boostedRate, new anchor.BN(until))
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    const effectiveRate = (commitment: PublicKey) =>
+      program.methods
+        .effectiveRewardRate()
+        .accountsStrict({ commitment, focusProgram: focusProgramPda })
+        .view();
+
+    // during the window
+    expect((await effectiveRate(maxParamsCommitmentPda)).toString()).to.equal(boostedRate.toString());
+    const locked = await program.account.focusCommitment.fetch(simulationCommitmentPda);
+    expect((await effectiveRate(simulationCommitmentPda)).toString()).to.equal(locked.lockedRewardRateBps.toString());
+
+    // after the window
+    await sleep(10000);
+    expect((await effectiveRate(maxParamsCommitmentPda)).toString()).to.equal(baseRate.toString());
+  });
+
+  // Test 15: Dust drain leaves outstanding bonuses covered
+  it("Drains reward pool dust to the treasury without touching reserved bonuses", async () => {
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const treasury = await getAssociatedTokenAddress(tokenMint, wallet.publicKey);
+
+    await program.methods
+      .setTreasury()
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        treasury: treasury,
+        authority: wallet.publicKey,
+      })
+      .rpc({ commitment: 'confirmed' });
+
+    const drain = (amount: anchor.BN) =>
+      program.methods
+        .drainRewardPoolDust(amount)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          rewardPool: rewardPoolPda,
+          treasury: treasury,
+          vaultAuthority: vaultAuthorityPda,
+          tokenMint: tokenMint,
+          authority: wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    // the active commitments above have reserved their bonuses
+    const programData = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programData.outstandingBonusLiability.toNumber()).to.be.greaterThan(0);
+
+    const poolBefore = new anchor.BN((await provider.connection.getTokenAccountBalance(rewardPoolPda)).value.amount);
+    await drain(new anchor.BN(1_000));
+    const poolAfter = new anchor.BN((await provider.connection.getTokenAccountBalance(rewardPoolPda)).value.amount);
+    expect(poolBefore.sub(poolAfter).toNumber()).to.equal(1_000);
+    expect(poolAfter.gte(programData.outstandingBonusLiability)).to.be.true;
+
+    // a whole token is not dust
+    try {
+      await drain(new anchor.BN(1_000_000_000));
+      expect.fail("Should have rejected draining a whole token");
+    } catch (error) {
+      expect(error.toString()).to.include("DustAmountTooLarge");
+    }
+  });
+
+  // Test 16: Sponsored commitments pay out to the sponsor, not the performer
+  it("Creates a sponsored commitment whose payout is routed to the sponsor", async () => {
+    const performer = Keypair.generate();
+    await provider.sendAndConfirm(new anchor.web3.Transaction().add(
+      SystemProgram.transfer({
+        fromPubkey: wallet.publicKey,
+        toPubkey: performer.publicKey,
+        lamports: 50000000,
+      })
+    ));
+
+    const [performerProfilePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("user_profile"), performer.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .createUserProfile()
+      .accountsStrict({
+        userProfile: performerProfilePda,
+        user: performer.publicKey,
+        focusProgram: focusProgramPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([performer])
+      .rpc({ commitment: 'confirmed' });
+
+    const betId = new anchor.BN(300);
+    const betStake = new anchor.BN(1_000_000);
+    const [betPda, betVaultPda] = findCommitmentPdas(performer.publicKey, betId);
+    const sponsorBalanceBefore = await provider.connection.getTokenAccountBalance(userTokenAccount);
+
+    await program.methods
+      .createSponsoredCommitment(betId, betStake, 1, 1)
+      .accountsStrict({
+        commitment: betPda,
+        performerProfile: performerProfilePda,
+        focusProgram: focusProgramPda,
+        performer: performer.publicKey,
+        sponsor: userKeypair.publicKey,
+        sponsorTokenAccount: userTokenAccount,
+        vault: betVaultPda,
+        vaultAuthority: vaultAuthorityPda,
+        tokenMint: tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        instructionsSysvar: null,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    // the performer owns the commitment, the sponsor funded it
+    const bet = await program.account.focusCommitment.fetch(betPda);
+    expect(bet.user.toString()).to.equal(performer.publicKey.toString());
+    expect(bet.sponsor.toString()).to.equal(userKeypair.publicKey.toString());
+    const sponsorBalanceAfter = await provider.connection.getTokenAccountBalance(userTokenAccount);
+    expect(Number(sponsorBalanceBefore.value.amount) - Number(sponsorBalanceAfter.value.amount)).to.equal(betStake.toNumber());
+    const vaultBalance = await provider.connection.getTokenAccountBalance(betVaultPda);
+    expect(vaultBalance.value.amount).to.equal(betStake.toString());
+
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const claim = (payoutTokenAccount: PublicKey) =>
+      program.methods
+        .claimRewards()
+        .accountsStrict({
+          commitment: betPda,
+          userProfile: performerProfilePda,
+          focusProgram: focusProgramPda,
+          user: performer.publicKey,
+          payoutTokenAccount,
+          vault: betVaultPda,
+          rewardPool: rewardPoolPda,
+          tokenMint: tokenMint,
+          habitAttestation: null,
+          globalStats: globalStatsPda,
+          referrerProfile: null,
+          referrerTokenAccount: null,
+          treasury: null,
+          charity: null,
+          vaultAuthority: vaultAuthorityPda,
+          mintAuthority: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([performer])
+        .rpc();
+
+    // the performer can't redirect the payout to themselves
+    const performerTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      wallet.payer,
+      tokenMint,
+      performer.publicKey
+    );
+    try {
+      await claim(performerTokenAccount);
+      expect.fail("Should have rejected a payout account not owned by the sponsor");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidPayoutAccount");
+    }
+
+    // the sponsor's account passes validation; the claim then waits for the commitment to end
+    try {
+      await claim(userTokenAccount);
+      expect.fail("Should not claim before the commitment ends");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentNotEnded");
+    }
+  });
+
+  // Test 17: Dashboard bundle matches the individually-read values
+  it("Returns a dashboard bundle matching individual reads", async () => {
+    const commitments = [maxParamsCommitmentPda, simulationCommitmentPda];
+    const dashboard = await program.methods
+      .getDashboard()
+      .accountsStrict({
+        userProfile: userProfilePda,
+        focusProgram: focusProgramPda,
+      })
+      .remainingAccounts(commitments.map(pubkey => ({ pubkey, isWritable: false, isSigner: false })))
+      .view();
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    expect(dashboard.currentStreak).to.equal(profile.currentStreak);
+    expect(dashboard.bestStreak).to.equal(profile.bestStreak);
+    expect(dashboard.totalSessionsCompleted.toString()).to.equal(profile.totalSessionsCompleted.toString());
+
+    // only active commitments are summarised
+    const active: PublicKey[] = [];
+    for (const pubkey of commitments) {
+      if ((await program.account.focusCommitment.fetch(pubkey)).isActive) {
+        active.push(pubkey);
+      }
+    }
+    expect(dashboard.commitments.length).to.equal(active.length);
+
+    for (const summary of dashboard.commitments) {
+      const commitment = await program.account.focusCommitment.fetch(summary.commitment);
+      const eligibleAt = await program.methods
+        .nextSessionEligibleAt()
+        .accountsStrict({ commitment: summary.commitment, focusProgram: focusProgramPda })
+        .view();
+      const rate = await program.methods
+        .effectiveRewardRate()
+        .accountsStrict({ commitment: summary.commitment, focusProgram: focusProgramPda })
+        .view();
+
+      expect(summary.remainingSessionsToday).to.be.at.most(commitment.sessionsPerDay);
+      // both reads land at the same second, or the view's clock moved on
+      expect(summary.nextSessionEligibleAt.toNumber()).to.be.at.most(eligibleAt.toNumber());
+      const projected = commitment.amountStaked.add(commitment.amountStaked.mul(rate).div(new anchor.BN(10_000)));
+      expect(summary.projectedReward.toString()).to.equal(projected.toString());
+    }
+  });
+
+  // Test 18: Keeper view for commitments nearing their deadline
+  it("Flags commitments expiring within a window", async () => {
+    const expiry = (withinSecs: number) =>
+      program.methods
+        .isExpiringSoon(new anchor.BN(withinSecs))
+        .accountsStrict({ commitment: maxParamsCommitmentPda })
+        .view();
+
+    const { secsRemaining } = await expiry(0);
+    expect(secsRemaining.toNumber()).to.be.greaterThan(0);
+
+    // leave a minute of slack either side for the clock moving between calls
+    const inside = await expiry(secsRemaining.toNumber() + 60);
+    expect(inside.expiringSoon).to.be.true;
+    const outside = await expiry(secsRemaining.toNumber() - 60);
+    expect(outside.expiringSoon).to.be.false;
+  });
+
+  // Test 19: Opting a commitment into milestone auto-staking
+  it("Opts a commitment into auto-staking rewards on streak milestones", async () => {
+    const setAutoStake = (commitment: PublicKey, enabled: boolean) =>
+      program.methods
+        .setAutoStakeOnMilestone(enabled)
+        .accountsStrict({
+          commitment,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc({ commitment: 'confirmed' });
+
+    let commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.autoStakeOnMilestone).to.be.false;
+    const stakeBefore = commitment.amountStaked;
+
+    await setAutoStake(maxParamsCommitmentPda, true);
+    commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.autoStakeOnMilestone).to.be.true;
+    // nothing moves until complete_session reaches a milestone
+    expect(commitment.amountStaked.toString()).to.equal(stakeBefore.toString());
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    expect(profile.rewardsAutoStaked.toNumber()).to.equal(0);
+
+    await setAutoStake(maxParamsCommitmentPda, false);
+  });
+
+  // Test 20: Per-commitment failure threshold
+  it("Raises a commitment's failure threshold within the tier bounds", async () => {
+    const setThreshold = (bps: number) =>
+      program.methods
+        .setFailThreshold(new anchor.BN(bps))
+        .accountsStrict({
+          commitment: maxParamsCommitmentPda,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc({ commitment: 'confirmed' });
+
+    let commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.failThresholdBps.toNumber()).to.equal(7500, "Should default to 75%");
+
+    // a stricter user: anything under 90% is a failure
+    await setThreshold(9000);
+    commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.failThresholdBps.toNumber()).to.equal(9000);
+
+    // can't be more lenient than the default or stricter than the bonus tier
+    for (const bps of [7499, 9001]) {
+      try {
+        await setThreshold(bps);
+        expect.fail(`Should have rejected a threshold of ${bps} bps`);
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidFailThreshold");
+      }
+    }
+
+    await setThreshold(7500);
+  });
+
+  // Test 21: Program-wide cap on total staked
+  it("Rejects commitments that would push total_staked past the cap", async () => {
+    const setCap = (cap: anchor.BN) =>
+      program.methods
+        .setMaxTotalStaked(cap)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    const capStake = new anchor.BN(1_000_000);
+    const programData = await program.account.focusProgram.fetch(focusProgramPda);
+    // room for exactly one more commitment of capStake
+    await setCap(programData.totalStaked.add(capStake));
+
+    try {
+      const over = await createCommitment(
+        new anchor.BN(400), capStake.addn(1), 1, 1, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(over.success).to.be.false;
+      expect(over.error.toString()).to.include("TvlCapExceeded");
+
+      const within = await createCommitment(
+        new anchor.BN(401), capStake, 1, 1, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(within.success).to.be.true;
+
+      const atCap = await program.account.focusProgram.fetch(focusProgramPda);
+      expect(atCap.totalStaked.toString()).to.equal(atCap.maxTotalStaked.toString());
+    } finally {
+      await setCap(new anchor.BN(0));
+    }
+  });
+
+  // Test 22: Commitment days are counted from start_timestamp, not UTC midnight
+  it("Gives a fresh commitment a full first day whatever the time of creation", async () => {
+    const result = await createCommitment(
+      new anchor.BN(402), new anchor.BN(1_000_000), 1, 1, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+
+    const commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+    const startHourUtc = new Date(commitment.startTimestamp.toNumber() * 1000).getUTCHours();
+
+    // even one created at 23:00 UTC keeps day 0 for 24 hours rather than rolling over at midnight
+    const { secsRemaining } = await program.methods
+      .isExpiringSoon(new anchor.BN(0))
+      .accountsStrict({ commitment: result.commitmentPda })
+      .view();
+    expect(secsRemaining.toNumber(), `created at ${startHourUtc}:00 UTC`).to.be.greaterThan(86400 - 60);
+
+    const eligibleAt = await program.methods
+      .nextSessionEligibleAt()
+      .accountsStrict({ commitment: result.commitmentPda, focusProgram: focusProgramPda })
+      .view();
+    expect(eligibleAt.toNumber()).to.be.lessThan(commitment.startTimestamp.toNumber() + 86400);
+  });
+
+  // Test 23: Streak airdrops need the tier's best streak
+  it("Rejects a streak airdrop tier the user hasn't reached", async () => {
+    const [airdropPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("airdrop_pool")],
+      program.programId
+    );
+    const claimTier = (tier: number) =>
+      program.methods
+        .claimStreakAirdrop(tier)
+        .accountsStrict({
+          userProfile: userProfilePda,
+          focusProgram: focusProgramPda,
+          airdropPool: airdropPoolPda,
+          vaultAuthority: vaultAuthorityPda,
+          userTokenAccount: userTokenAccount,
+          user: userKeypair.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([userKeypair])
+        .rpc();
+
+    // the 30-day tier; once reached it pays out a single time and a repeat fails with AirdropAlreadyClaimed
+    try {
+      await claimTier(1);
+      expect.fail("Should not pay a tier above the user's best streak");
+    } catch (error) {
+      expect(error.toString()).to.include("StreakTierNotReached");
+    }
+
+    try {
+      await claimTier(3);
+      expect.fail("Should reject a tier that doesn't exist");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidAirdropTier");
+    }
+
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    expect(profile.claimedAirdropTiers).to.equal(0);
+  });
+
+  // Test 24: Audit trail of the rate a claim used
+  it("Only reports the claimed reward rate once a commitment is claimed", async () => {
+    const commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.effectiveRewardRateUsed.toNumber()).to.equal(0);
+
+    // claim_rewards stores the same locked or boosted rate it computed the bonus with
+    try {
+      await program.methods
+        .claimedRewardRate()
+        .accountsStrict({ commitment: maxParamsCommitmentPda })
+        .view();
+      expect.fail("Should not report a rate for an unclaimed commitment");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentNotClaimed");
+    }
+  });
+
+  // Test 25: Reviving a commitment after its deadline
+  it("Rejects reviving a commitment before its deadline", async () => {
+    // a revival restarts the term with the same stake and is allowed once per commitment
+    try {
+      await program.methods
+        .reviveCommitment()
+        .accountsStrict({
+          commitment: maxParamsCommitmentPda,
+          focusProgram: focusProgramPda,
+          userProfile: userProfilePda,
+          globalStats: null,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc();
+      expect.fail("Should not revive a commitment that is still running");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentNotEnded");
+    }
+
+    const commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.revivalCount).to.equal(0);
+  });
+
+  // Test 26: One missed session is forgiven at claim time
+  it("Leaves the free miss unused until the commitment is claimed", async () => {
+    // claim_rewards credits one extra completed session, capped at the expected count,
+    // so 8 of 10 sessions claims at the 90% bonus tier instead of the 80% stake-back tier
+    const commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.freeMissUsed).to.be.false;
+    expect(commitment.isActive).to.be.true;
+  });
+
+  // Test 27: Vaults only move to a new mint after migrate_mint
+  it("Rejects migrating a vault before the mint has been migrated", async () => {
+    // migrate_mint switches the shared program config to a new token for every later test,
+    // so only the guard is exercised here
+    const [migratedVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("migrated_vault"), userKeypair.publicKey.toBuffer(), commitmentIds.maxParams.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [legacyReservePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("legacy_reserve")],
+      program.programId
+    );
+    const [migrationReservePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("migration_reserve")],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .migrateVault()
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          commitment: maxParamsCommitmentPda,
+          vault: maxParamsVaultPda,
+          migratedVault: migratedVaultPda,
+          legacyReserve: legacyReservePda,
+          migrationReserve: migrationReservePda,
+          vaultAuthority: vaultAuthorityPda,
+          newMint: tokenMint,
+          user: userKeypair.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([userKeypair])
+        .rpc();
+      expect.fail("Should not migrate a vault without a mint migration");
+    } catch (error) {
+      expect(error.toString()).to.include("MintMigrationNotStarted");
+    }
+
+    const commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.vaultMigrated).to.be.false;
+  });
+
+  // Test 28: Sessions still needed for each reward tier
+  it("Counts the sessions needed to reach the stake-back and bonus tiers", async () => {
+    const tierProgress = (commitment: PublicKey) =>
+      program.methods
+        .sessionsToNextTier()
+        .accountsStrict({ commitment })
+        .view();
+
+    // 10 sessions a day for 30 days: 225 and 270 of 300, minus the one forgiven miss
+    let progress = await tierProgress(maxParamsCommitmentPda);
+    expect(progress.sessionsToStakeBack).to.equal(224);
+    expect(progress.sessionsToBonus).to.equal(269);
+
+    // a single-session commitment is already covered by its free miss
+    progress = await tierProgress(commitmentPda);
+    expect(progress.sessionsToStakeBack).to.equal(0);
+    expect(progress.sessionsToBonus).to.equal(0);
+  });
+
+  // Test 29: Full exit is only open once the program is deprecated
+  it("Rejects exiting with the full stake before the program is deprecated", async () => {
+    // deprecate_program is one-way and would stop every later test from creating commitments,
+    // so only the guard is exercised here
+    try {
+      await program.methods
+        .exitOnDeprecation()
+        .accountsStrict({
+          commitment: maxParamsCommitmentPda,
+          focusProgram: focusProgramPda,
+          user: userKeypair.publicKey,
+          payoutTokenAccount: userTokenAccount,
+          vault: maxParamsVaultPda,
+          vaultAuthority: vaultAuthorityPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([userKeypair])
+        .rpc();
+      expect.fail("Should not allow a tier-free exit while the program is live");
+    } catch (error) {
+      expect(error.toString()).to.include("ProgramNotDeprecated");
+    }
+
+    const commitment = await program.account.focusCommitment.fetch(maxParamsCommitmentPda);
+    expect(commitment.isActive).to.be.true;
+  });
+
+  // Test 30: Auto-renewing a commitment into a new term
+  it("Only auto-renews an opted-in commitment once its term has ended", async () => {
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const setAutoRenew = (autoRenew: boolean) =>
+      program.methods
+        .setAutoRenew(autoRenew)
+        .accountsStrict({
+          commitment: simulationCommitmentPda,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc();
+    // any keeper may renew; the bonus still goes to the sponsor's account
+    const renew = (rewardPool: PublicKey | null = rewardPoolPda, globalStats: PublicKey | null = globalStatsPda) =>
+      program.methods
+        .autoRenewCommitment()
+        .accountsStrict({
+          commitment: simulationCommitmentPda,
+          userProfile: userProfilePda,
+          focusProgram: focusProgramPda,
+          payoutTokenAccount: userTokenAccount,
+          rewardPool,
+          tokenMint: tokenMint,
+          habitAttestation: null,
+          globalStats,
+          vaultAuthority: vaultAuthorityPda,
+          mintAuthority: null,
+          keeper: wallet.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    try {
+      await renew();
+      expect.fail("Should not renew a commitment that hasn't opted in");
+    } catch (error) {
+      expect(error.toString()).to.include("AutoRenewDisabled");
+    }
+
+    await setAutoRenew(true);
+    let commitment = await program.account.focusCommitment.fetch(simulationCommitmentPda);
+    expect(commitment.autoRenew).to.be.true;
+
+    // renewing pays out without the session records strict verification recounts
+    const setStrict = (strict: boolean) =>
+      program.methods
+        .setStrictClaimVerification(strict)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+    await setStrict(true);
+    try {
+      await renew();
+      expect.fail("Should not renew under strict claim verification");
+    } catch (error) {
+      expect(error.toString()).to.include("StrictClaimVerification");
+    } finally {
+      await setStrict(false);
+    }
+
+    try {
+      await renew();
+      expect.fail("Should not renew before the term ends");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentNotEnded");
+    }
+    // a keeper can leave out the pool and stats accounts, which only some renewals use
+    try {
+      await renew(null, null);
+      expect.fail("Should not renew before the term ends");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentNotEnded");
+    }
+
+    await setAutoRenew(false);
+    commitment = await program.account.focusCommitment.fetch(simulationCommitmentPda);
+    expect(commitment.autoRenew).to.be.false;
+  });
+
+  // Test 31: Minimum stake in whole tokens
+  it("Scales the whole-token minimum stake by the mint's decimals", async () => {
+    const setMinStake = (tokens: number) =>
+      program.methods
+        .setMinStake(new anchor.BN(tokens))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    // 1 token is 10^6 raw units on a 6 decimal mint and 10^9 on a 9 decimal one
+    const mint = await getMint(provider.connection, tokenMint);
+    const oneToken = new anchor.BN(10).pow(new anchor.BN(mint.decimals));
+    await setMinStake(1);
+
+    try {
+      const below = await createCommitment(
+        new anchor.BN(403), oneToken.subn(1), 1, 1, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(below.success).to.be.false;
+      expect(below.error.toString()).to.include("StakeBelowMinimum");
+
+      const atMinimum = await createCommitment(
+        new anchor.BN(404), oneToken, 1, 1, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(atMinimum.success).to.be.true;
+    } finally {
+      await setMinStake(0);
+    }
+  });
+
+  // Test 32: Cancelling early costs a penalty scaled by the days remaining
+  it("Cancels a commitment with the full early penalty on its first day", async () => {
+    const cancelStake = new anchor.BN(1_000_000);
+    const result = await createCommitment(
+      new anchor.BN(405), cancelStake, 1, 2, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+
+    const programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.maxEarlyPenaltyBps.toNumber()).to.equal(2500);
+    const treasury = programState.treasury;
+    const balance = async (account: PublicKey) =>
+      new anchor.BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+    const userBefore = await balance(userTokenAccount);
+    const treasuryBefore = await balance(treasury);
+
+    await program.methods
+      .cancelCommitment()
+      .accountsStrict({
+        commitment: result.commitmentPda,
+        focusProgram: focusProgramPda,
+        user: userKeypair.publicKey,
+        userTokenAccount: userTokenAccount,
+        vault: result.vaultPda,
+        treasury: treasury,
+        vaultAuthority: vaultAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    // 2 of 2 days remaining: the whole 25%. cancelling on the second day would cost 12.5%
+    const penalty = cancelStake.muln(2500).divn(10_000);
+    expect((await balance(userTokenAccount)).sub(userBefore).toString()).to.equal(cancelStake.sub(penalty).toString());
+    expect((await balance(treasury)).sub(treasuryBefore).toString()).to.equal(penalty.toString());
+
+    const commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+    expect(commitment.isActive).to.be.false;
+
+    // the emptied vault no longer covers the stake, so a claim must fail before any transfer
+    try {
+      await program.methods
+        .claimRewards()
+        .accountsStrict({
+          commitment: result.commitmentPda,
+          userProfile: userProfilePda,
+          focusProgram: focusProgramPda,
+          user: userKeypair.publicKey,
+          payoutTokenAccount: userTokenAccount,
+          vault: result.vaultPda,
+          rewardPool: null,
+          tokenMint: tokenMint,
+          habitAttestation: null,
+          globalStats: null,
+          referrerProfile: null,
+          referrerTokenAccount: null,
+          treasury: null,
+          charity: null,
+          vaultAuthority: vaultAuthorityPda,
+          mintAuthority: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([userKeypair])
+        .rpc();
+      expect.fail("Claim against an underfunded vault should have failed");
+    } catch (error) {
+      expect(error.toString()).to.include("VaultMissing");
+    }
+    expect((await balance(userTokenAccount)).sub(userBefore).toString()).to.equal(cancelStake.sub(penalty).toString());
+  });
+
+  it("Rejects bulk claims of commitments that haven't ended, lost their stake or aren't paired with their vault", async () => {
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const claimAll = (pairs: PublicKey[], rewardPool: PublicKey | null = rewardPoolPda) =>
+      program.methods
+        .claimAllRewards()
+        .accountsStrict({
+          userProfile: userProfilePda,
+          focusProgram: focusProgramPda,
+          user: userKeypair.publicKey,
+          payoutTokenAccount: userTokenAccount,
+          rewardPool,
+          tokenMint: tokenMint,
+          habitAttestation: null,
+          globalStats: globalStatsPda,
+          referrerProfile: null,
+          referrerTokenAccount: null,
+          treasury: null,
+          charity: null,
+          vaultAuthority: vaultAuthorityPda,
+          mintAuthority: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(pairs.map(pubkey => ({ pubkey, isWritable: true, isSigner: false })))
+        .signers([userKeypair])
+        .rpc();
+
+    const expectError = async (pairs: PublicKey[], errorName: string, rewardPool: PublicKey | null = rewardPoolPda) => {
+      try {
+        await claimAll(pairs, rewardPool);
+        expect.fail(`Should fail with ${errorName}`);
+      } catch (error) {
+        expect(error.toString()).to.include(errorName);
+      }
+    };
+
+    await expectError([commitmentPda], "InvalidCommitmentAccounts");
+    await expectError([commitmentPda, maxParamsVaultPda], "InvalidCommitmentAccounts");
+    // each commitment is held to claim_rewards' rules, so one unfinished term fails the batch
+    await expectError([commitmentPda, vaultPda, maxParamsCommitmentPda, maxParamsVaultPda], "CommitmentNotEnded");
+    // the pool is optional, so a batch without it gets as far as the term checks
+    await expectError([commitmentPda, vaultPda], "CommitmentNotEnded", null);
+    // the vault Test 32's cancel emptied fails clearly, before any of the batch's transfers
+    const [cancelledPda, cancelledVaultPda] = findCommitmentPdas(userKeypair.publicKey, new anchor.BN(405));
+    await expectError([cancelledPda, cancelledVaultPda, commitmentPda, vaultPda], "VaultMissing");
+
+    // custom payout routing is only honored by single claims
+    const setPartialRefundSource = (partialRefundSource: object) =>
+      program.methods
+        .setFundingSources({ rewardPool: {} } as any, { vault: {} } as any, partialRefundSource as any)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+    await setPartialRefundSource({ rewardPool: {} });
+    try {
+      await expectError([commitmentPda, vaultPda], "CustomFundingSources");
+    } finally {
+      await setPartialRefundSource({ vault: {} });
+    }
+
+    // strict verification needs each commitment's session records, which a batch can't carry
+    const setStrict = (strict: boolean) =>
+      program.methods
+        .setStrictClaimVerification(strict)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+    await setStrict(true);
+    try {
+      await expectError([commitmentPda, vaultPda], "StrictClaimVerification");
+    } finally {
+      await setStrict(false);
+    }
+  });
+
+  it("Rejects day counts whose deadline can't be represented", async () => {
+    // u8::MAX days is the longest term a client can ask for; none may get past creation
+    for (const totalDays of [255, 0]) {
+      const result = await createCommitment(
+        new anchor.BN(406), stakeAmount, 1, totalDays, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(result.success).to.be.false;
+      expect(result.error.toString()).to.include("InvalidDayCount");
+    }
+  });
+
+  it("Leaves the failure summary empty until a claim lands below the threshold", async () => {
+    const commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(commitment.isActive).to.be.true;
+    expect(commitment.failureSuccessBps).to.equal(0);
+    expect(commitment.failureSessionsMissed).to.equal(0);
+    expect(commitment.failureDaysShort).to.equal(0);
+    // no session has completed, so no day counts as active yet
+    expect(commitment.activeDayFlags).to.equal(0);
+  });
+
+  it("Drops weekend days from a weekend-exempt commitment's expected sessions", async () => {
+    const result = await createCommitment(
+      new anchor.BN(407), stakeAmount, 1, 7, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const setSkipWeekends = (skipWeekends: boolean, dayOffsetSecs: number) =>
+      program.methods
+        .setSkipWeekends(skipWeekends, dayOffsetSecs)
+        .accountsStrict({
+          commitment: result.commitmentPda,
+          userProfile: userProfilePda,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc();
+    const tierProgress = () =>
+      program.methods
+        .sessionsToNextTier()
+        .accountsStrict({ commitment: result.commitmentPda })
+        .view();
+
+    // 7 expected sessions: 6 and 7 needed, minus the forgiven miss
+    let progress = await tierProgress();
+    expect(progress.sessionsToStakeBack).to.equal(5);
+    expect(progress.sessionsToBonus).to.equal(6);
+
+    try {
+      await setSkipWeekends(true, 15 * 3600);
+      expect.fail("Should reject an offset beyond 14 hours");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidTimezoneOffset");
+    }
+
+    // any 7 consecutive days hold exactly one weekend, leaving 5 expected sessions
+    const profileBefore = await program.account.userProfile.fetch(userProfilePda);
+    await setSkipWeekends(true, -5 * 3600);
+    const commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+    expect(commitment.skipWeekends).to.be.true;
+    expect(commitment.dayOffsetSecs).to.equal(-5 * 3600);
+    progress = await tierProgress();
+    expect(progress.sessionsToStakeBack).to.equal(3);
+    expect(progress.sessionsToBonus).to.equal(4);
+
+    // the all-time expected count drops the two weekend sessions counted at creation
+    const profileAfter = await program.account.userProfile.fetch(userProfilePda);
+    expect(profileBefore.totalExpectedSessionsAllTime.sub(profileAfter.totalExpectedSessionsAllTime).toNumber())
+      .to.equal(2);
+  });
+
+  it("Tracks reserved bonus liabilities against the reward pool", async () => {
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const solvency = () =>
+      program.methods
+        .checkSolvency()
+        .accountsStrict({ focusProgram: focusProgramPda, rewardPool: rewardPoolPda })
+        .view();
+
+    const before = await solvency();
+    const result = await createCommitment(
+      new anchor.BN(408), stakeAmount, 1, 2, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+    const reserved = commitment.reservedBonus;
+    expect(reserved.toString()).to.equal(stakeAmount.mul(commitment.lockedRewardRateBps).divn(10_000).toString());
+
+    const afterCreate = await solvency();
+    expect(afterCreate.outstandingBonusLiability.sub(before.outstandingBonusLiability).toString())
+      .to.equal(reserved.toString());
+    expect(afterCreate.isSolvent).to.equal(afterCreate.poolBalance.gte(afterCreate.outstandingBonusLiability));
+
+    // leaving the commitment releases its reservation, as a claim does
+    const { treasury } = await program.account.focusProgram.fetch(focusProgramPda);
+    await program.methods
+      .cancelCommitment()
+      .accountsStrict({
+        commitment: result.commitmentPda,
+        focusProgram: focusProgramPda,
+        user: userKeypair.publicKey,
+        userTokenAccount: userTokenAccount,
+        vault: result.vaultPda,
+        treasury: treasury,
+        vaultAuthority: vaultAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    const afterCancel = await solvency();
+    expect(afterCancel.outstandingBonusLiability.toString()).to.equal(before.outstandingBonusLiability.toString());
+    if (!afterCancel.outstandingBonusLiability.isZero()) {
+      expect(afterCancel.solvencyRatioBps.toString()).to.equal(
+        afterCancel.poolBalance.muln(10_000).div(afterCancel.outstandingBonusLiability).toString()
+      );
+    }
+  });
+
+  it("Tracks the all-time completion rate across every commitment", async () => {
+    const before = await program.account.userProfile.fetch(userProfilePda);
+    const result = await createCommitment(
+      new anchor.BN(409), stakeAmount, 2, 3, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+
+    // the new commitment's 2 x 3 sessions are added to the lifetime total
+    const profile = await program.account.userProfile.fetch(userProfilePda);
+    expect(profile.totalExpectedSessionsAllTime.toNumber()).to.equal(before.totalExpectedSessionsAllTime.toNumber() + 6);
+
+    const dashboard = await program.methods
+      .getDashboard()
+      .accountsStrict({
+        userProfile: userProfilePda,
+        focusProgram: focusProgramPda,
+      })
+      .view();
+    const expectedBps = Math.min(
+      10_000,
+      Math.floor(profile.totalSessionsCompleted.toNumber() * 10_000 / profile.totalExpectedSessionsAllTime.toNumber())
+    );
+    expect(dashboard.allTimeCompletionBps.toNumber()).to.equal(expectedBps);
+  });
+
+  it("Stores a reminder offset for notifiers, shorter than a day", async () => {
+    const setReminderOffset = (offsetSecs: number) =>
+      program.methods
+        .setReminderOffset(offsetSecs)
+        .accountsStrict({
+          commitment: commitmentPda,
+          user: userKeypair.publicKey,
+        })
+        .signers([userKeypair])
+        .rpc({ commitment: 'confirmed' });
+
+    let commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(commitment.reminderOffsetSecs).to.equal(0);
+
+    // nudge two hours before each commitment day ends
+    await setReminderOffset(2 * 60 * 60);
+    commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(commitment.reminderOffsetSecs).to.equal(7200);
+
+    try {
+      await setReminderOffset(24 * 60 * 60);
+      expect.fail("A reminder a whole day ahead should be rejected");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidReminderOffset");
+    }
+
+    await setReminderOffset(0);
+    commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(commitment.reminderOffsetSecs).to.equal(0);
+  });
+
+  it("Exports a versioned snapshot that decodes back to the on-chain commitment", async () => {
+    const blob: Buffer = Buffer.from(await program.methods
+      .exportCommitment()
+      .accountsStrict({ commitment: commitmentPda })
+      .view());
+
+    // version byte, then the commitment's address
+    expect(blob[0]).to.equal(1);
+    expect(new PublicKey(blob.subarray(1, 33)).toString()).to.equal(commitmentPda.toString());
+
+    // then the account data exactly as stored, minus the 8 byte discriminator
+    const accountInfo = await provider.connection.getAccountInfo(commitmentPda);
+    const stateLength = accountInfo.data.length - 8;
+    const state = blob.subarray(33, 33 + stateLength);
+    expect(state.equals(accountInfo.data.subarray(8))).to.be.true;
+    const decoded = program.coder.accounts.decode(
+      "focusCommitment",
+      Buffer.concat([accountInfo.data.subarray(0, 8), state])
+    );
+    const commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(decoded.user.toString()).to.equal(commitment.user.toString());
+    expect(decoded.amountStaked.toString()).to.equal(commitment.amountStaked.toString());
+    expect(decoded.sessionsCompleted).to.equal(commitment.sessionsCompleted);
+    expect(decoded.startTimestamp.toString()).to.equal(commitment.startTimestamp.toString());
+
+    // and the aggregate session stats: expected sessions u16, success bps u64, active days u8
+    const statsOffset = 33 + stateLength;
+    expect(blob.length).to.equal(statsOffset + 2 + 8 + 1);
+    const expectedSessions = commitment.sessionsPerDay * commitment.totalDays;
+    expect(blob.readUInt16LE(statsOffset)).to.equal(expectedSessions);
+    // one missed session is forgiven until the commitment is complete
+    const credited = commitment.sessionsCompleted < expectedSessions && !commitment.freeMissUsed
+      ? commitment.sessionsCompleted + 1
+      : commitment.sessionsCompleted;
+    const successBps = Math.min(
+      10_000,
+      Math.floor((credited * 10_000 + commitment.partialCreditBps.toNumber()) / expectedSessions)
+    );
+    expect(Number(blob.readBigUInt64LE(statsOffset + 2))).to.equal(successBps);
+    expect(blob.readUInt8(statsOffset + 10)).to.equal(0);
+  });
+
+  it("Previews the partial refund of a failing commitment", async () => {
+    const preview = await program.methods
+      .previewClaim()
+      .accountsStrict({
+        commitment: commitmentPda,
+        focusProgram: focusProgramPda,
+        tokenMint: tokenMint,
+        habitAttestation: null,
+      })
+      .view();
+
+    // no sessions yet, so the term is well below the failure threshold
+    const commitment = await program.account.focusCommitment.fetch(commitmentPda);
+    expect(preview.successBps.toNumber()).to.be.lessThan(commitment.failThresholdBps.toNumber());
+    expect(preview.tier).to.deep.equal({ partialRefund: {} });
+
+    // claim_rewards refunds 75% of the stake below the threshold and pays no bonus; the claim
+    // itself has to wait for the deadline, which the tests can't reach
+    const expectedRefund = commitment.amountStaked.muln(75).divn(100);
+    expect(preview.refundAmount.toString()).to.equal(expectedRefund.toString());
+    expect(preview.refundBps.toNumber()).to.equal(7500);
+    expect(preview.bonusAmount.toNumber()).to.equal(0);
+    expect(preview.giftAmount.toNumber()).to.equal(0);
+  });
+
+  it("Rejects a long commitment backed by a dust stake", async () => {
+    const setMinStakePerDay = (amount: number) =>
+      program.methods
+        .setMinStakePerDay(new anchor.BN(amount))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    // 30 days at 10_000 raw units a day needs a 300_000 stake
+    await setMinStakePerDay(10_000);
+    try {
+      const dust = await createCommitment(
+        new anchor.BN(410), new anchor.BN(1_000), 1, 30, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(dust.success).to.be.false;
+      expect(dust.error.toString()).to.include("StakeTooLowForDuration");
+
+      const backed = await createCommitment(
+        new anchor.BN(411), new anchor.BN(300_000), 1, 30, userKeypair, userProfilePda, userTokenAccount
+      );
+      expect(backed.success).to.be.true;
+    } finally {
+      await setMinStakePerDay(0);
+    }
+  });
+
+  it("Checkpoints a commitment's progress once per interval", async function() {
+    this.timeout(60000);
+    const setCheckpointInterval = (secs: number) =>
+      program.methods
+        .setCheckpointInterval(new anchor.BN(secs))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    const result = await createCommitment(
+      new anchor.BN(412), new anchor.BN(1_000_000), 2, 10, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const checkpoint = () =>
+      program.methods
+        .checkpointCommitment()
+        .accountsStrict({
+          commitment: result.commitmentPda,
+          focusProgram: focusProgramPda,
+          keeper: wallet.publicKey,
+        })
+        .rpc({ commitment: 'confirmed' });
+
+    // a real deployment checkpoints weekly; 3 seconds lets the test reach two intervals
+    await setCheckpointInterval(3);
+    try {
+      try {
+        await checkpoint();
+        expect.fail("Should not checkpoint before the first interval has passed");
+      } catch (error) {
+        expect(error.toString()).to.include("CheckpointNotDue");
+      }
+
+      await sleep(4000);
+      await checkpoint();
+      try {
+        await checkpoint();
+        expect.fail("Should take one checkpoint per interval");
+      } catch (error) {
+        expect(error.toString()).to.include("CheckpointNotDue");
+      }
+
+      await sleep(3000);
+      await checkpoint();
+
+      const commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+      expect(commitment.checkpointCount).to.equal(2);
+      const [first, second] = commitment.checkpoints;
+      expect(second.timestamp.toNumber()).to.be.greaterThan(first.timestamp.toNumber());
+      expect(second.timestamp.toNumber() - commitment.startTimestamp.toNumber()).to.be.at.least(6);
+      for (const snapshot of [first, second]) {
+        // no sessions yet, and the first commitment day hasn't ended
+        expect(snapshot.sessionsCompleted).to.equal(0);
+        expect(snapshot.expectedSessions).to.equal(0);
+      }
+      expect(commitment.checkpoints[2].timestamp.toNumber()).to.equal(0);
+    } finally {
+      await setCheckpointInterval(0);
+    }
+  });
+
+  it("Tracks a friend's gift apart from the stake and pays it out with the refund", async () => {
+    const friend = Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: friend.publicKey,
+          lamports: 50000000,
+        })
+      )
+    );
+    const friendTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      wallet.payer,
+      tokenMint,
+      friend.publicKey,
+      { commitment: 'confirmed' }
+    );
+    const gift = new anchor.BN(400_000);
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      tokenMint,
+      friendTokenAccount,
+      wallet.publicKey,
+      gift.toNumber(),
+      [],
+      { commitment: 'confirmed' }
+    );
+
+    const stake = new anchor.BN(1_000_000);
+    const result = await createCommitment(
+      new anchor.BN(413), stake, 1, 2, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const programBefore = await program.account.focusProgram.fetch(focusProgramPda);
+    const sponsor = (amount: anchor.BN) =>
+      program.methods
+        .sponsorCommitment(amount)
+        .accountsStrict({
+          commitment: result.commitmentPda,
+          focusProgram: focusProgramPda,
+          vault: result.vaultPda,
+          donorTokenAccount: friendTokenAccount,
+          donor: friend.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([friend])
+        .rpc({ commitment: 'confirmed' });
+
+    try {
+      await sponsor(new anchor.BN(0));
+      expect.fail("Should have rejected an empty gift");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidGiftAmount");
+    }
+
+    await sponsor(gift);
+    let commitment = await program.account.focusCommitment.fetch(result.commitmentPda);
+    expect(commitment.sponsoredAmount.toString()).to.equal(gift.toString());
+    expect(commitment.amountStaked.toString()).to.equal(stake.toString());
+    const balance = async (account: PublicKey) =>
+      new anchor.BN((await provider.connection.getTokenAccountBalance(account)).value.amount);
+    expect((await balance(result.vaultPda)).toString()).to.equal(stake.add(gift).toString());
+    // the gift isn't stake, so it neither counts toward the TVL nor earns a bonus
+    const programAfter = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programAfter.totalStaked.toString()).to.equal(programBefore.totalStaked.toString());
+
+    // cancelling on the first day refunds 75% of the stake, and the same share of the gift
+    const treasury = programAfter.treasury;
+    const userBefore = await balance(userTokenAccount);
+    const treasuryBefore = await balance(treasury);
+    await program.methods
+      .cancelCommitment()
+      .accountsStrict({
+        commitment: result.commitmentPda,
+        focusProgram: focusProgramPda,
+        user: userKeypair.publicKey,
+        userTokenAccount: userTokenAccount,
+        vault: result.vaultPda,
+        treasury: treasury,
+        vaultAuthority: vaultAuthorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([userKeypair])
+      .rpc({ commitment: 'confirmed' });
+
+    const refunded = stake.add(gift).muln(7500).divn(10_000);
+    expect((await balance(userTokenAccount)).sub(userBefore).toString()).to.equal(refunded.toString());
+    expect((await balance(treasury)).sub(treasuryBefore).toString()).to.equal(stake.add(gift).sub(refunded).toString());
+    expect((await balance(result.vaultPda)).toNumber()).to.equal(0);
+
+    try {
+      await sponsor(gift);
+      expect.fail("Should only accept gifts for active commitments");
+    } catch (error) {
+      expect(error.toString()).to.include("CommitmentInactive");
+    }
+  });
+
+  it("Creates a staged program as one batch of back-to-back commitments", async () => {
+    const phases = [
+      { commitmentId: new anchor.BN(414), amount: new anchor.BN(1_000_000), sessionsPerDay: 1, totalDays: 2 },
+      { commitmentId: new anchor.BN(415), amount: new anchor.BN(2_000_000), sessionsPerDay: 2, totalDays: 3 },
+      { commitmentId: new anchor.BN(416), amount: new anchor.BN(3_000_000), sessionsPerDay: 3, totalDays: 4 },
+    ];
+    const pdas = phases.map(phase => findCommitmentPdas(userKeypair.publicKey, phase.commitmentId));
+    const total = phases.reduce((sum, phase) => sum.add(phase.amount), new anchor.BN(0));
+    const createBatch = (totalAmount: anchor.BN) =>
+      program.methods
+        .createCommitmentBatch(phases, totalAmount)
+        .accountsStrict({
+          userProfile: userProfilePda,
+          focusProgram: focusProgramPda,
+          user: userKeypair.publicKey,
+          userTokenAccount: userTokenAccount,
+          vaultAuthority: vaultAuthorityPda,
+          tokenMint: tokenMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          instructionsSysvar: null,
+        })
+        .remainingAccounts(pdas.flatMap(([commitment, vault]) => [
+          { pubkey: commitment, isWritable: true, isSigner: false },
+          { pubkey: vault, isWritable: true, isSigner: false },
+        ]))
+        .signers([userKeypair])
+        .rpc({ commitment: 'confirmed' });
+
+    try {
+      await createBatch(total.subn(1));
+      expect.fail("Should have rejected a total that doesn't match the phases");
+    } catch (error) {
+      expect(error.toString()).to.include("BatchTotalMismatch");
+    }
+
+    const userBefore = (await provider.connection.getTokenAccountBalance(userTokenAccount)).value.amount;
+    await createBatch(total);
+    const userAfter = (await provider.connection.getTokenAccountBalance(userTokenAccount)).value.amount;
+    expect(new anchor.BN(userBefore).sub(new anchor.BN(userAfter)).toString()).to.equal(total.toString());
+
+    const commitments = await Promise.all(pdas.map(([commitment]) => program.account.focusCommitment.fetch(commitment)));
+    for (const [i, phase] of phases.entries()) {
+      const vaultBalance = await provider.connection.getTokenAccountBalance(pdas[i][1]);
+      expect(vaultBalance.value.amount).to.equal(phase.amount.toString());
+      expect(commitments[i].amountStaked.toString()).to.equal(phase.amount.toString());
+      expect(commitments[i].sessionsPerDay).to.equal(phase.sessionsPerDay);
+      expect(commitments[i].isActive).to.be.true;
+      // each phase opens when the previous one ends
+      if (i > 0) {
+        const previousEnd = commitments[i - 1].startTimestamp.addn(commitments[i - 1].totalDays * 86400);
+        expect(commitments[i].startTimestamp.toString()).to.equal(previousEnd.toString());
+      }
+    }
+
+    // a staged phase reports its start as the earliest eligible session
+    const eligibleAt = await program.methods
+      .nextSessionEligibleAt()
+      .accountsStrict({ commitment: pdas[2][0], focusProgram: focusProgramPda })
+      .view();
+    expect(eligibleAt.toString()).to.equal(commitments[2].startTimestamp.toString());
+  });
+
+  it("Applies the program's rate policy when the rate changes after creation", async () => {
+    const result = await createCommitment(
+      new anchor.BN(417), new anchor.BN(1_000_000), 1, 2, userKeypair, userProfilePda, userTokenAccount
+    );
+    expect(result.success).to.be.true;
+    const programData = await program.account.focusProgram.fetch(focusProgramPda);
+    const createdRate = (await program.account.focusCommitment.fetch(result.commitmentPda)).lockedRewardRateBps;
+    const raisedRate = createdRate.muln(2);
+    const cutRate = createdRate.divn(2);
+
+    const setRewardRate = (rate: anchor.BN) =>
+      program.methods
+        .setRewardRate(rate)
+        .accountsStrict({ focusProgram: focusProgramPda, authority: wallet.publicKey })
+        .rpc({ commitment: 'confirmed' });
+    const setRatePolicy = (ratePolicy: object) =>
+      program.methods
+        .setRatePolicy(ratePolicy as any)
+        .accountsStrict({ focusProgram: focusProgramPda, authority: wallet.publicKey })
+        .rpc({ commitment: 'confirmed' });
+    // the rate claim_rewards would apply right now
+    const claimRate = async () =>
+      (await program.methods
+        .effectiveRewardRate()
+        .accountsStrict({ commitment: result.commitmentPda, focusProgram: focusProgramPda })
+        .view()).toString();
+
+    try {
+      await setRewardRate(raisedRate);
+      await setRatePolicy({ lockAtCreation: {} });
+      expect(await claimRate()).to.equal(createdRate.toString());
+      await setRatePolicy({ useCurrent: {} });
+      expect(await claimRate()).to.equal(raisedRate.toString());
+      await setRatePolicy({ betterOfBoth: {} });
+      expect(await claimRate()).to.equal(raisedRate.toString());
+
+      // after a cut, only BetterOfBoth keeps the rate from creation
+      await setRewardRate(cutRate);
+      expect(await claimRate()).to.equal(createdRate.toString());
+      await setRatePolicy({ useCurrent: {} });
+      expect(await claimRate()).to.equal(cutRate.toString());
+      await setRatePolicy({ lockAtCreation: {} });
+      expect(await claimRate()).to.equal(createdRate.toString());
+    } finally {
+      await setRewardRate(programData.rewardRateBps);
+      await setRatePolicy({ useCurrent: {} });
     }
   });
-});
\ No newline at end of file
+});
@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { PublicKey, SystemProgram, Keypair, Transaction } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, createMint } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAssociatedTokenAddress } from "@solana/spl-token";
 import { expect } from "chai";
 import { F0x01 } from "../target/types/f0x01";
 
@@ -17,7 +17,7 @@ describe("F0x01 Initialize Tests", () => {
   let focusTokenMint: PublicKey;
   
   //test parameters
-  const rewardRate = new anchor.BN(100); // set reward rate
+  const rewardRate = new anchor.BN(10_000); // 100% reward rate, in basis points
   
   // Helper function to fund a wallet using your main wallet instead of airdrops
   async function fundWalletFromMain(destination: PublicKey, amountInLamports: number) {
@@ -55,6 +55,26 @@ describe("F0x01 Initialize Tests", () => {
     // console.log("Focus Token Mint:", focusTokenMint.toString());
   });
 
+  it("Fails to initialize with a zero reward rate", async () => {
+    try {
+      await program.methods
+        .initializeProgram(new anchor.BN(0))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          focusTokenMint: focusTokenMint,
+          authority: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+
+      expect.fail("Should have rejected a zero reward rate");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidRewardRate");
+    }
+  });
+
   it("Initializes the program with correct state", async () => {
     try {
       // call the initialize instruction
@@ -78,8 +98,9 @@ describe("F0x01 Initialize Tests", () => {
       expect(programState.authority.toString()).to.equal(wallet.publicKey.toString(), "Authority doesn't match");
       expect(programState.totalUsers.toNumber()).to.equal(0, "Total users should be 0");
       expect(programState.totalStaked.toNumber()).to.equal(0, "Total staked should be 0");
-      expect(programState.rewardRate.toNumber()).to.equal(rewardRate.toNumber(), "Reward rate doesn't match");
+      expect(programState.rewardRateBps.toNumber()).to.equal(rewardRate.toNumber(), "Reward rate doesn't match");
       expect(programState.focusTokenMint.toString()).to.equal(focusTokenMint.toString(), "Token mint doesn't match");
+      expect(programState.minCommitmentDays).to.equal(0, "Minimum commitment days should default to 0");
       
       //verify the bump is set correctly
       const [expectedPda, bump] = PublicKey.findProgramAddressSync(
@@ -93,6 +114,181 @@ describe("F0x01 Initialize Tests", () => {
     }
   });
 
+  it("Initializes and funds the reward pool", async () => {
+    const [rewardPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_pool")],
+      program.programId
+    );
+    const [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeRewardPool()
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        rewardPool: rewardPoolPda,
+        vaultAuthority: vaultAuthorityPda,
+        tokenMint: focusTokenMint,
+        authority: wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    // fund the pool from the authority's own token account
+    const funderTokenAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      wallet.payer,
+      focusTokenMint,
+      wallet.publicKey
+    );
+    const poolFunding = 1_000_000_000_000; // 1000 tokens with 9 decimals
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      focusTokenMint,
+      funderTokenAccount,
+      wallet.publicKey,
+      poolFunding
+    );
+
+    await program.methods
+      .fundRewardPool(new anchor.BN(poolFunding))
+      .accountsStrict({
+        rewardPool: rewardPoolPda,
+        funderTokenAccount: funderTokenAccount,
+        funder: wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const poolBalance = await provider.connection.getTokenAccountBalance(rewardPoolPda);
+    expect(poolBalance.value.amount).to.equal(poolFunding.toString());
+  });
+
+  it("Initializes the streak airdrop pool and tier amounts", async () => {
+    const [airdropPoolPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("airdrop_pool")],
+      program.programId
+    );
+    const [vaultAuthorityPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_authority")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeAirdropPool()
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        airdropPool: airdropPoolPda,
+        vaultAuthority: vaultAuthorityPda,
+        tokenMint: focusTokenMint,
+        authority: wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const funderTokenAccount = await getAssociatedTokenAddress(focusTokenMint, wallet.publicKey);
+    const airdropFunding = 100_000_000_000; // 100 tokens with 9 decimals
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      focusTokenMint,
+      funderTokenAccount,
+      wallet.publicKey,
+      airdropFunding
+    );
+    await program.methods
+      .fundAirdropPool(new anchor.BN(airdropFunding))
+      .accountsStrict({
+        airdropPool: airdropPoolPda,
+        funderTokenAccount: funderTokenAccount,
+        funder: wallet.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // 7, 30 and 100 day tiers
+    const tierAmounts = [1_000_000_000, 5_000_000_000, 20_000_000_000].map(amount => new anchor.BN(amount));
+    await program.methods
+      .setStreakAirdropAmounts(tierAmounts)
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+
+    const programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakAirdropAmounts.map(amount => amount.toString()))
+      .to.deep.equal(tierAmounts.map(amount => amount.toString()));
+    const poolBalance = await provider.connection.getTokenAccountBalance(airdropPoolPda);
+    expect(poolBalance.value.amount).to.equal(airdropFunding.toString());
+  });
+
+  it("Initializes global payout stats at zero", async () => {
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeGlobalStats()
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        globalStats: globalStatsPda,
+        authority: wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // principal returned is the difference between the two totals
+    const stats = await program.account.globalStats.fetch(globalStatsPda);
+    expect(stats.totalRewardsPaid.toString()).to.equal("0");
+    expect(stats.totalBonusPaid.toString()).to.equal("0");
+  });
+
+  it("Rejects rolling over a stats period before the minimum interval", async () => {
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const [periodStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("period_stats"), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    let stats = await program.account.globalStats.fetch(globalStatsPda);
+    expect(stats.period.toNumber()).to.equal(0);
+    expect(stats.periodRewardsPaid.toString()).to.equal("0");
+    expect(stats.periodBonusPaid.toString()).to.equal("0");
+
+    // the first period only just started, a rollover after a full day can't be reached from the tests
+    try {
+      await program.methods
+        .rolloverPeriodStats()
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          globalStats: globalStatsPda,
+          periodStats: periodStatsPda,
+          authority: wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      expect.fail("Should have rejected an early rollover");
+    } catch (error) {
+      expect(error.toString()).to.include("StatsPeriodNotEnded");
+    }
+
+    stats = await program.account.globalStats.fetch(globalStatsPda);
+    expect(stats.period.toNumber()).to.equal(0);
+    expect(await provider.connection.getAccountInfo(periodStatsPda)).to.equal(null);
+  });
+
   it("Should fail when initializing with an already initialized PDA", async () => {
     try {
       //attempt to initialize the program again with the same PDA
@@ -118,7 +314,7 @@ describe("F0x01 Initialize Tests", () => {
 
   it("Allows different reward rates to be set", async () => {
     // Create new program instance with a different PDA for testing different parameters
-    const differentRewardRate = new anchor.BN(200);
+    const differentRewardRate = new anchor.BN(20_000);
     const differentAuthority = Keypair.generate();
     
     // Fund the new authority using your main wallet instead of an airdrop
@@ -158,6 +354,532 @@ describe("F0x01 Initialize Tests", () => {
     }
   });
 
+  it("Bounds the clock drift tolerance used to reject skewed sessions", async () => {
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.maxClockDriftBps.toNumber()).to.equal(5000, "Drift tolerance should default to 50%");
+
+    // more than 100% drift would never reject a mismatched session
+    try {
+      await program.methods
+        .setMaxClockDrift(new anchor.BN(10_001))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have rejected an out of range drift tolerance");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidBasisPoints");
+    }
+
+    await program.methods
+      .setMaxClockDrift(new anchor.BN(2500))
+      .accountsStrict({
+        focusProgram: focusProgramPda,
+        authority: wallet.publicKey,
+      })
+      .rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.maxClockDriftBps.toNumber()).to.equal(2500);
+  });
+
+  it("Accepts a session duration tolerance only up to the 60 second cap", async () => {
+    const setTolerance = (secs: number) =>
+      program.methods
+        .setDurationTolerance(new anchor.BN(secs))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.durationToleranceSecs.toNumber()).to.equal(0, "Tolerance should default to 0");
+
+    await setTolerance(60);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.durationToleranceSecs.toNumber()).to.equal(60);
+
+    for (const secs of [61, -1]) {
+      try {
+        await setTolerance(secs);
+        expect.fail(`Should have rejected a tolerance of ${secs}s`);
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidDurationTolerance");
+      }
+    }
+
+    await setTolerance(0);
+  });
+
+  it("Bounds the final-day catch-up gap by the normal 30 minute gap", async () => {
+    const setGap = (secs: number) =>
+      program.methods
+        .setCatchUpGap(new anchor.BN(secs))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // 10 minutes between sessions on a commitment's last day
+    await setGap(10 * 60);
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.catchUpGapSecs.toNumber()).to.equal(600);
+
+    try {
+      await setGap(30 * 60 + 1);
+      expect.fail("Should have rejected a catch-up gap longer than the normal gap");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidCatchUpGap");
+    }
+
+    await setGap(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.catchUpGapSecs.toNumber()).to.equal(0);
+  });
+
+  it("Switches streaks between counting started and completed sessions", async () => {
+    const setStreakOnStart = (streakOnStart: boolean) =>
+      program.methods
+        .setStreakOnStart(streakOnStart)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakOnStart).to.be.false;
+
+    // start_session extends the streak instead of complete_session; a day is only counted once
+    await setStreakOnStart(true);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakOnStart).to.be.true;
+
+    await setStreakOnStart(false);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakOnStart).to.be.false;
+  });
+
+  it("Configures rounding of claim bonuses to a token granularity", async () => {
+    const setRounding = (roundBonus: boolean, bonusPrecision: number) =>
+      program.methods
+        .setBonusRounding(roundBonus, bonusPrecision)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // whole tokens: a 1.234567 token bonus on a 6 decimal mint pays 1 token, the rest stays pooled
+    await setRounding(true, 0);
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.roundBonus).to.be.true;
+    expect(programState.bonusPrecision).to.equal(0);
+
+    await setRounding(true, 2);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusPrecision).to.equal(2);
+
+    await setRounding(false, 0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.roundBonus).to.be.false;
+  });
+
+  it("Routes forfeited stakes to the treasury, the reward pool or a charity", async () => {
+    const { focusTokenMint: programMint } = await program.account.focusProgram.fetch(focusProgramPda);
+    const setDestination = (forfeitDestination: object, charity: PublicKey | null) =>
+      program.methods
+        .setForfeitDestination(forfeitDestination as any)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          charity,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.forfeitDestination).to.deep.equal({ treasury: {} });
+
+    // failures fund the bonuses of those who succeed
+    await setDestination({ rewardPool: {} }, null);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.forfeitDestination).to.deep.equal({ rewardPool: {} });
+
+    if (programState.charity.equals(PublicKey.default)) {
+      try {
+        await setDestination({ charity: {} }, null);
+        expect.fail("Should require a charity account first");
+      } catch (error) {
+        expect(error.toString()).to.include("CharityNotSet");
+      }
+    }
+
+    const charityAccount = await createAssociatedTokenAccount(
+      provider.connection,
+      wallet.payer,
+      programMint,
+      Keypair.generate().publicKey
+    );
+    await setDestination({ charity: {} }, charityAccount);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.forfeitDestination).to.deep.equal({ charity: {} });
+    expect(programState.charity.toString()).to.equal(charityAccount.toString());
+
+    await setDestination({ treasury: {} }, null);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.forfeitDestination).to.deep.equal({ treasury: {} });
+  });
+
+  it("Requires a minimum number of distinct active days for any refund", async () => {
+    const setMinActiveDays = (minActiveDays: number) =>
+      program.methods
+        .setMinActiveDays(minActiveDays)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    try {
+      await setMinActiveDays(31);
+      expect.fail("Should reject more days than a commitment can last");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidDayCount");
+    }
+
+    await setMinActiveDays(3);
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.minActiveDays).to.equal(3);
+
+    await setMinActiveDays(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.minActiveDays).to.equal(0);
+  });
+
+  it("Caps claim bonuses at a share of the stake", async () => {
+    const setCap = (bonusCapRatioBps: number) =>
+      program.methods
+        .setBonusCap(new anchor.BN(bonusCapRatioBps))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // at 100% a 150% rate on a 10 token stake pays a 10 token bonus, not 15
+    await setCap(10_000);
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusCapRatioBps.toNumber()).to.equal(10_000);
+
+    await setCap(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusCapRatioBps.toNumber()).to.equal(0);
+  });
+
+  it("Routes each claim payout to its configured funding source", async () => {
+    const setSources = (bonusSource: object, principalSource: object, partialRefundSource: object) =>
+      program.methods
+        .setFundingSources(bonusSource as any, principalSource as any, partialRefundSource as any)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // stakes come back out of their vaults and bonuses out of the pool by default
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusSource).to.deep.equal({ rewardPool: {} });
+    expect(programState.principalSource).to.deep.equal({ vault: {} });
+    expect(programState.partialRefundSource).to.deep.equal({ vault: {} });
+
+    // the vault only holds the stake, so paying the bonus from it would fail every bonus claim
+    try {
+      await setSources({ vault: {} }, { vault: {} }, { vault: {} });
+      expect.fail("Should have rejected the vault as the bonus source");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidBonusSource");
+    }
+
+    await setSources({ rewardPool: {} }, { rewardPool: {} }, { vault: {} });
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusSource).to.deep.equal({ rewardPool: {} });
+    expect(programState.principalSource).to.deep.equal({ rewardPool: {} });
+    expect(programState.partialRefundSource).to.deep.equal({ vault: {} });
+
+    await setSources({ rewardPool: {} }, { vault: {} }, { vault: {} });
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.bonusSource).to.deep.equal({ rewardPool: {} });
+    expect(programState.principalSource).to.deep.equal({ vault: {} });
+  });
+
+  it("Sets the auto-fail threshold for commitments that fall too far behind", async () => {
+    const setThreshold = (bps: number) =>
+      program.methods
+        .setAutoFailThreshold(new anchor.BN(bps))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.autoFailThresholdBps.toNumber()).to.equal(0, "Auto-fail should start off");
+
+    try {
+      await setThreshold(10_001);
+      expect.fail("Should reject a threshold above 100%");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidFailThreshold");
+    }
+
+    await setThreshold(7_500);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.autoFailThresholdBps.toNumber()).to.equal(7_500);
+
+    await setThreshold(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.autoFailThresholdBps.toNumber()).to.equal(0);
+  });
+
+  it("Toggles strict claim verification against session records", async () => {
+    const setStrict = (strict: boolean) =>
+      program.methods
+        .setStrictClaimVerification(strict)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.strictClaimVerification).to.be.false;
+
+    await setStrict(true);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.strictClaimVerification).to.be.true;
+
+    await setStrict(false);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.strictClaimVerification).to.be.false;
+  });
+
+  it("Pays a continuous bonus that rises with completion under a reward curve", async () => {
+    const setCurve = (floorBps: number, ceilingBps: number, maxBonusBps: number) =>
+      program.methods
+        .setRewardCurve(new anchor.BN(floorBps), new anchor.BN(ceilingBps), new anchor.BN(maxBonusBps))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+    const curveBonus = async (successBps: number) =>
+      (await program.methods
+        .rewardCurveBonus(new anchor.BN(1_000_000), new anchor.BN(successBps))
+        .accountsStrict({ focusProgram: focusProgramPda })
+        .view()).toNumber();
+
+    // tiered by default
+    expect(await curveBonus(10_000)).to.equal(0);
+
+    // a floor below the default failure threshold would pay bonuses on failed terms
+    for (const [floorBps, ceilingBps] of [[7000, 9500], [8500, 8500], [8000, 10_001]]) {
+      try {
+        await setCurve(floorBps, ceilingBps, 2000);
+        expect.fail(`Should have rejected a curve from ${floorBps} to ${ceilingBps} bps`);
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidRewardCurve");
+      }
+    }
+
+    // no bonus at 80% or below, 20% of the stake from 95%
+    await setCurve(8000, 9500, 2000);
+    try {
+      expect(await curveBonus(7500)).to.equal(0);
+      expect(await curveBonus(8000)).to.equal(0);
+      expect(await curveBonus(8750)).to.equal(100_000);
+      expect(await curveBonus(9500)).to.equal(200_000);
+      expect(await curveBonus(10_000)).to.equal(200_000);
+      // over-completion doesn't raise the bonus past the ceiling's
+      expect(await curveBonus(15_000)).to.equal(200_000);
+
+      // monotonic, and 1 bps more completion never adds more than one step of the line
+      const step = Math.ceil(200_000 / 1500);
+      let previous = await curveBonus(8000);
+      for (const successBps of [8001, 8300, 8301, 8900, 9000, 9001, 9499, 9500]) {
+        const bonus = await curveBonus(successBps);
+        expect(bonus).to.be.at.least(previous);
+        expect(bonus).to.equal(Math.floor(200_000 * (successBps - 8000) / 1500));
+        previous = bonus;
+      }
+      expect((await curveBonus(8301)) - (await curveBonus(8300))).to.be.at.most(step);
+    } finally {
+      await setCurve(0, 0, 0);
+    }
+    expect(await curveBonus(10_000)).to.equal(0);
+  });
+
+  it("Bounds the cooldown between a user's bonus claims", async () => {
+    const setCooldown = (secs: number) =>
+      program.methods
+        .setClaimCooldown(new anchor.BN(secs))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.claimCooldownSecs.toNumber()).to.equal(0, "Claims should not be rate-limited by default");
+
+    // a long cooldown would hold bonuses back indefinitely
+    try {
+      await setCooldown(7 * 86400 + 1);
+      expect.fail("Should have rejected a cooldown over a week");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidClaimCooldown");
+    }
+
+    // blocking a rapid second claim needs two finished terms, which the tests can't reach
+    await setCooldown(3600);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.claimCooldownSecs.toNumber()).to.equal(3600);
+
+    await setCooldown(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.claimCooldownSecs.toNumber()).to.equal(0);
+  });
+
+  it("Bounds the streak grace period between commitments", async () => {
+    const setGrace = (streakGraceDays: number) =>
+      program.methods
+        .setStreakGraceDays(streakGraceDays)
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakGraceDays).to.equal(0, "Gaps between commitments should break streaks by default");
+
+    try {
+      await setGrace(31);
+      expect.fail("Should have rejected a grace period longer than a term");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidDayCount");
+    }
+
+    // claiming needs a finished term, so carrying a streak over the gap isn't reachable here
+    await setGrace(3);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakGraceDays).to.equal(3);
+
+    await setGrace(0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.streakGraceDays).to.equal(0);
+  });
+
+  it("Only mints claim bonuses once the program holds the mint authority", async () => {
+    const [mintAuthorityPda, mintAuthorityBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority")],
+      program.programId
+    );
+    const { focusTokenMint: programMint } = await program.account.focusProgram.fetch(focusProgramPda);
+    const setMinting = (mintBonuses: boolean, capPerEpoch: number) =>
+      program.methods
+        .setBonusMinting(mintBonuses, new anchor.BN(capPerEpoch))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          tokenMint: programMint,
+          mintAuthority: mintAuthorityPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // the test wallet still owns the mint, so bonuses keep coming from the reward pool
+    try {
+      await setMinting(true, 1_000_000);
+      expect.fail("Should require the mint_authority PDA to own the mint");
+    } catch (error) {
+      expect(error.toString()).to.include("MintAuthorityNotHeld");
+    }
+
+    await setMinting(false, 1_000_000);
+    const programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.mintBonuses).to.be.false;
+    expect(programState.mintCapPerEpoch.toNumber()).to.equal(1_000_000);
+    expect(programState.mintAuthorityBump).to.equal(mintAuthorityBump);
+    expect(programState.epochMinted.toNumber()).to.equal(0);
+  });
+
+  it("Configures a community challenge window and its extra bonus", async () => {
+    const setChallenge = (start: number, end: number, bonusBps: number) =>
+      program.methods
+        .setChallengeWindow(new anchor.BN(start), new anchor.BN(end), new anchor.BN(bonusBps))
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          authority: wallet.publicKey,
+        })
+        .rpc();
+
+    // commitments whose whole term runs inside the window earn 5% on top of the normal bonus
+    const now = Math.floor(Date.now() / 1000);
+    await setChallenge(now, now + 60 * 86400, 500);
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.challengeStart.toNumber()).to.equal(now);
+    expect(programState.challengeEnd.toNumber()).to.equal(now + 60 * 86400);
+    expect(programState.challengeBonusBps.toNumber()).to.equal(500);
+
+    try {
+      await setChallenge(now, now, 500);
+      expect.fail("Should have rejected an empty challenge window");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidChallengeWindow");
+    }
+
+    try {
+      await setChallenge(now, now + 86400, 10_001);
+      expect.fail("Should have rejected a challenge bonus above 100%");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidBasisPoints");
+    }
+
+    // a zero bonus turns the challenge off
+    await setChallenge(now, now + 86400, 0);
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.challengeBonusBps.toNumber()).to.equal(0);
+  });
+
+  it("Manages the allowlist of programs that may create commitments via CPI", async () => {
+    const router = Keypair.generate().publicKey;
+    const accounts = { focusProgram: focusProgramPda, authority: wallet.publicKey };
+
+    await program.methods.addAuthorizedCaller(router).accountsStrict(accounts).rpc();
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.authorizedCallerCount).to.equal(1);
+    expect(programState.authorizedCallers[0].toString()).to.equal(router.toString());
+
+    try {
+      await program.methods.addAuthorizedCaller(router).accountsStrict(accounts).rpc();
+      expect.fail("Should have rejected a duplicate caller");
+    } catch (error) {
+      expect(error.toString()).to.include("CallerAlreadyAuthorized");
+    }
+
+    await program.methods.removeAuthorizedCaller(router).accountsStrict(accounts).rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.authorizedCallerCount).to.equal(0);
+
+    try {
+      await program.methods.removeAuthorizedCaller(router).accountsStrict(accounts).rpc();
+      expect.fail("Should have rejected removing an unknown caller");
+    } catch (error) {
+      expect(error.toString()).to.include("CallerNotFound");
+    }
+  });
+
   it("Verifies program account ownership", async () => {
     const programState = await program.account.focusProgram.fetch(focusProgramPda);
     
@@ -167,8 +889,124 @@ describe("F0x01 Initialize Tests", () => {
       "Program account should be owned by the program");
     
     // Verify account data size matches expected space
-    const expectedSpace = 8 + 32 + 1 + 8 + 8 + 8 + 32; 
+    const expectedSpace = 8 + 32 + 1 + 8 + 8 + 8 + 32 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 4 + 8 + 32 * 4 + 1 + 8 + 8 + 8 * 3 + 8 + 8 + 8 + 8 + 1 + 32 + 8 + 1 + 1 + 1 + 1 + 8 + 32 + 8 + 8 + 1 + 1 + 8 + 8 + 8 + 1 + 8 + 1 + 32 + 1 + 8 + 1 + 1 + 1 + 8 + 1 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 8; 
     expect(accountInfo.data.length).to.equal(expectedSpace, 
       "Account data size doesn't match expected space");
   });
-});
\ No newline at end of file
+  it("Bounds reward rate tuning and waits for completion data", async () => {
+    const [globalStatsPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("global_stats")],
+      program.programId
+    );
+    const accounts = { focusProgram: focusProgramPda, authority: wallet.publicKey };
+
+    try {
+      await program.methods
+        .setRateTuning(new anchor.BN(1500), new anchor.BN(500), new anchor.BN(100))
+        .accountsStrict(accounts)
+        .rpc();
+      expect.fail("Should have rejected a min rate above the max rate");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidRateTuning");
+    }
+
+    await program.methods
+      .setRateTuning(new anchor.BN(500), new anchor.BN(1500), new anchor.BN(100))
+      .accountsStrict(accounts)
+      .rpc();
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.tuneMinRateBps.toNumber()).to.equal(500);
+    expect(programState.tuneMaxRateBps.toNumber()).to.equal(1500);
+    expect(programState.tuneMaxStepBps.toNumber()).to.equal(100);
+
+    // no term has been claimed yet, so there is no completion rate to tune against
+    try {
+      await program.methods
+        .autoTuneRewardRate()
+        .accountsStrict({
+          focusProgram: focusProgramPda,
+          globalStats: globalStatsPda,
+          keeper: wallet.publicKey,
+        })
+        .rpc();
+      expect.fail("Should have rejected tuning without claimed terms");
+    } catch (error) {
+      expect(error.toString()).to.include("NoCompletionData");
+    }
+    const stats = await program.account.globalStats.fetch(globalStatsPda);
+    expect(stats.termsClaimed.toNumber()).to.equal(0);
+
+    await program.methods
+      .setRateTuning(new anchor.BN(0), new anchor.BN(0), new anchor.BN(0))
+      .accountsStrict(accounts)
+      .rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.rewardRateBps.toNumber()).to.equal(rewardRate.toNumber());
+  });
+
+  it("Bounds the perfect-day streak bonus", async () => {
+    const accounts = { focusProgram: focusProgramPda, authority: wallet.publicKey };
+
+    try {
+      await program.methods.setPerfectStreakBonus(new anchor.BN(101)).accountsStrict(accounts).rpc();
+      expect.fail("Should have rejected a per-day bonus above 1%");
+    } catch (error) {
+      expect(error.toString()).to.include("InvalidPerfectStreakBonus");
+    }
+
+    // 0.5% of the stake per day of the best streak: a 5 day streak adds 2.5%
+    await program.methods.setPerfectStreakBonus(new anchor.BN(50)).accountsStrict(accounts).rpc();
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.perfectStreakBonusBps.toNumber()).to.equal(50);
+
+    await program.methods.setPerfectStreakBonus(new anchor.BN(0)).accountsStrict(accounts).rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.perfectStreakBonusBps.toNumber()).to.equal(0);
+  });
+
+  it("Configures the cluster's slot time", async () => {
+    const accounts = { focusProgram: focusProgramPda, authority: wallet.publicKey };
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.msPerSlot.toNumber()).to.equal(400);
+
+    for (const msPerSlot of [50, 5_000]) {
+      try {
+        await program.methods.setMsPerSlot(new anchor.BN(msPerSlot)).accountsStrict(accounts).rpc();
+        expect.fail(`Should have rejected a ${msPerSlot}ms slot time`);
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidMsPerSlot");
+      }
+    }
+
+    // an 800ms cluster halves the slots a session has to wait for
+    await program.methods.setMsPerSlot(new anchor.BN(800)).accountsStrict(accounts).rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.msPerSlot.toNumber()).to.equal(800);
+
+    await program.methods.setMsPerSlot(new anchor.BN(400)).accountsStrict(accounts).rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.msPerSlot.toNumber()).to.equal(400);
+  });
+
+  it("Bounds the early-bird hour and bonus", async () => {
+    const accounts = { focusProgram: focusProgramPda, authority: wallet.publicKey };
+
+    for (const [hour, bonusBps] of [[24, 100], [7, 501]]) {
+      try {
+        await program.methods.setEarlyBird(hour, new anchor.BN(bonusBps)).accountsStrict(accounts).rpc();
+        expect.fail(`Should have rejected hour ${hour} with a ${bonusBps} bps bonus`);
+      } catch (error) {
+        expect(error.toString()).to.include("InvalidEarlyBird");
+      }
+    }
+
+    await program.methods.setEarlyBird(7, new anchor.BN(200)).accountsStrict(accounts).rpc();
+    let programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.earlyBirdHour).to.equal(7);
+    expect(programState.earlyBirdBonusBps.toNumber()).to.equal(200);
+
+    await program.methods.setEarlyBird(0, new anchor.BN(0)).accountsStrict(accounts).rpc();
+    programState = await program.account.focusProgram.fetch(focusProgramPda);
+    expect(programState.earlyBirdHour).to.equal(0);
+  });
+});